@@ -9,6 +9,7 @@ use w3f_plonk_common::gadgets::booleanity::{BitColumn, Booleanity};
 use w3f_plonk_common::gadgets::ec::{AffineColumn, CondAdd};
 use w3f_plonk_common::gadgets::inner_prod::InnerProd;
 use w3f_plonk_common::gadgets::ProverGadget;
+use w3f_plonk_common::piop::aggregate_evaluations;
 use w3f_plonk_common::test_helpers::{random_bitvec, random_vec};
 
 fn bench_domain_creation(c: &mut Criterion) {
@@ -170,6 +171,42 @@ fn bench_te_cond_add_gadget(c: &mut Criterion) {
     group.finish();
 }
 
+fn bench_quotient(c: &mut Criterion) {
+    let rng = &mut test_rng();
+    let mut group = c.benchmark_group("quotient");
+    for log_n in [9, 10, 12] {
+        let n = 1usize << log_n;
+        let domain = Domain::<Fq>::with_zk_rows(n, 3);
+        let seed = EdwardsAffine::generator();
+
+        let bitmask = random_bitvec(domain.capacity - 1, 0.5, rng);
+        let points = random_vec::<EdwardsAffine, _>(domain.capacity - 1, rng);
+        let bitmask_col = BitColumn::init(bitmask, &domain);
+        let points_col = AffineColumn::column(points, &domain);
+        let gadget = CondAdd::init(bitmask_col, points_col, seed, &domain);
+        let constraints = gadget.constraints();
+        let alphas: Vec<Fq> = random_vec(constraints.len(), rng);
+        let agg_constraint = aggregate_evaluations(&constraints, &alphas);
+
+        group.bench_with_input(
+            BenchmarkId::new("interpolate_and_divide", n),
+            &agg_constraint,
+            |bench, agg_constraint| {
+                bench.iter(|| domain.compute_quotient(&agg_constraint.interpolate_by_ref()));
+            },
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new("divide_over_coset", n),
+            &agg_constraint,
+            |bench, agg_constraint| {
+                bench.iter(|| domain.divide_by_vanishing_poly_4x(agg_constraint));
+            },
+        );
+    }
+    group.finish();
+}
+
 criterion_group!(
     benches,
     bench_domain_creation,
@@ -177,5 +214,6 @@ criterion_group!(
     bench_booleanity_gadget,
     bench_inner_prod_gadget,
     bench_te_cond_add_gadget,
+    bench_quotient,
 );
 criterion_main!(benches);
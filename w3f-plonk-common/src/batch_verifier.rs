@@ -0,0 +1,130 @@
+use ark_ec::pairing::Pairing;
+use ark_std::rand::RngCore;
+use ark_std::vec::Vec;
+use w3f_pcs::pcs::kzg::params::KzgVerifierKey;
+use w3f_pcs::pcs::kzg::KZG;
+use w3f_pcs::pcs::PCS;
+
+use crate::kzg_acc::KzgAccumulator;
+use crate::piop::VerifierPiop;
+use crate::transcript::PlonkTranscript;
+use crate::verifier::Challenges;
+use crate::{ColumnsCommited, ColumnsEvaluated, Proof};
+
+/// A Plonk proof of any PIOP prepared for batch verification: the PIOP verifier is set up,
+/// and the challenges are restored from the proof transcript.
+pub struct PreparedItem<E, Piop, Commitments, Evaluations>
+where
+    E: Pairing,
+    Piop: VerifierPiop<E::ScalarField, <KZG<E> as PCS<E::ScalarField>>::C>,
+    Commitments: ColumnsCommited<E::ScalarField, <KZG<E> as PCS<E::ScalarField>>::C>,
+    Evaluations: ColumnsEvaluated<E::ScalarField>,
+{
+    piop: Piop,
+    proof: Proof<E::ScalarField, KZG<E>, Commitments, Evaluations>,
+    challenges: Challenges<E::ScalarField>,
+    entropy: [u8; 32],
+}
+
+impl<E, Piop, Commitments, Evaluations> PreparedItem<E, Piop, Commitments, Evaluations>
+where
+    E: Pairing,
+    Piop: VerifierPiop<E::ScalarField, <KZG<E> as PCS<E::ScalarField>>::C>,
+    Commitments: ColumnsCommited<E::ScalarField, <KZG<E> as PCS<E::ScalarField>>::C>,
+    Evaluations: ColumnsEvaluated<E::ScalarField>,
+{
+    /// - `fs_rng`: the rng derived from the transcript of the proof, see `PlonkVerifier::restore_fs_with_rng`,
+    ///   that makes the randomizers of the proof in the batch independent of the other proofs.
+    pub fn new<R: RngCore>(
+        piop: Piop,
+        proof: Proof<E::ScalarField, KZG<E>, Commitments, Evaluations>,
+        challenges: Challenges<E::ScalarField>,
+        fs_rng: &mut R,
+    ) -> Self {
+        let mut entropy = [0_u8; 32];
+        fs_rng.fill_bytes(&mut entropy);
+        Self {
+            piop,
+            proof,
+            challenges,
+            entropy,
+        }
+    }
+}
+
+/// Accumulating batch verifier for Plonk proofs of different PIOPs, that share the same KZG SRS.
+/// The proofs are settled in a single pairing check.
+///
+/// Holds its own transcript instance, cloned for every item pushed,
+/// so that the per-proof entropy can be folded in, see `PreparedItem::new`.
+/// The base transcript only needs to be deterministic, not proof-specific.
+pub struct PlonkBatchVerifier<E: Pairing, T>
+where
+    T: PlonkTranscript<E::ScalarField, KZG<E>>,
+{
+    acc: KzgAccumulator<E>,
+    transcript: T,
+}
+
+impl<E: Pairing, T> PlonkBatchVerifier<E, T>
+where
+    T: PlonkTranscript<E::ScalarField, KZG<E>>,
+{
+    pub fn new(kzg_vk: KzgVerifierKey<E>, transcript: T) -> Self {
+        Self {
+            acc: KzgAccumulator::new(kzg_vk),
+            transcript,
+        }
+    }
+
+    /// Returns an empty batch with the same key and transcript,
+    /// e.g. to accumulate a part of the items on another thread, and then `merge` it back.
+    pub fn fork(&self) -> Self {
+        Self::new(self.acc.kzg_vk().clone(), self.transcript.clone())
+    }
+
+    /// Accumulates a prepared proof into the batch.
+    pub fn push_prepared<Piop, Commitments, Evaluations>(
+        &mut self,
+        item: PreparedItem<E, Piop, Commitments, Evaluations>,
+    ) where
+        Piop: VerifierPiop<E::ScalarField, <KZG<E> as PCS<E::ScalarField>>::C>,
+        Commitments: ColumnsCommited<E::ScalarField, <KZG<E> as PCS<E::ScalarField>>::C>,
+        Evaluations: ColumnsEvaluated<E::ScalarField>,
+    {
+        let mut ts = self.transcript.clone();
+        ts._add_serializable(b"batch-entropy", &item.entropy);
+        self.acc
+            .accumulate(item.piop, item.proof, item.challenges, &mut ts.to_rng());
+    }
+
+    /// Merges the items of the other batch, see `KzgAccumulator::merge`.
+    pub fn merge(&mut self, other: Self) -> Result<(), ()> {
+        self.acc.merge(other.acc)
+    }
+
+    /// Number of the proofs in the batch.
+    pub fn len(&self) -> usize {
+        self.acc.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.acc.is_empty()
+    }
+
+    /// Verifies all the proofs in a single batched pairing check.
+    pub fn verify(&self) -> bool {
+        self.acc.verify()
+    }
+
+    /// Returns the indices of the failing proofs, in the order they were pushed, empty if the batch verifies.
+    /// See `KzgAccumulator::verify_and_identify`.
+    pub fn verify_and_identify(&self) -> Vec<usize> {
+        self.acc.verify_and_identify()
+    }
+
+    /// Returns the accumulator, e.g. to be serialized and merged with the accumulators of other shards.
+    pub fn into_accumulator(self) -> KzgAccumulator<E> {
+        self.acc
+    }
+}
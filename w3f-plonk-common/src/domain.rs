@@ -1,4 +1,4 @@
-use crate::FieldColumn;
+use crate::{CompactColumn, FieldColumn};
 use ark_ff::{batch_inversion, FftField, Field, Zero};
 use ark_poly::univariate::DensePolynomial;
 use ark_poly::{
@@ -8,6 +8,8 @@ use ark_std::{vec, vec::Vec};
 use getrandom_or_panic::getrandom_or_panic;
 
 // Domains for performing calculations with constraint polynomials of degree up to 4.
+// `x4` is a coset of the subgroup of size `4N`, so that the vanishing polynomial of `x1`
+// doesn't vanish over it, and the constraint polynomials can be divided by it pointwise.
 #[derive(Clone)]
 pub struct Domains<F: FftField> {
     pub x1: GeneralEvaluationDomain<F>,
@@ -19,7 +21,9 @@ impl<F: FftField> Domains<F> {
         let x1 = GeneralEvaluationDomain::<F>::new(n)
             .unwrap_or_else(|| panic!("No domain of size {}", n));
         let x4 = GeneralEvaluationDomain::<F>::new(4 * n)
+            .and_then(|x4| x4.get_coset(F::GENERATOR))
             .unwrap_or_else(|| panic!("No domain of size {}", 4 * n));
+        debug_assert_eq!(x4.size(), 4 * x1.size());
         Self { x1, x4 }
     }
 
@@ -36,19 +40,70 @@ impl<F: FftField> Domains<F> {
         }
     }
 
-    fn column_from_poly(&self, poly: DensePolynomial<F>) -> FieldColumn<F> {
-        debug_assert!(poly.degree() + 1 <= self.x1.size());
+    fn column_from_compact(&self, col: &CompactColumn<F>) -> FieldColumn<F> {
+        debug_assert!(col.poly.degree() + 1 <= self.x1.size());
+        let poly = col.poly.clone();
+        let evals = poly.evaluate_over_domain_by_ref(self.x1);
         let evals_4x = self.amplify(&poly);
-        let evals = evals_4x.evals.iter().step_by(4).cloned().collect();
-        let evals = Evaluations::from_vec_and_domain(evals, self.x1);
         FieldColumn {
             poly,
             evals,
             evals_4x,
-            payload_len: self.x1.size(),
+            payload_len: col.payload_len,
         }
     }
 
+    // The `i`-th Lagrange basis polynomial as a column, see `Domain::add_to_cells`.
+    fn l_i_column(&self, i: usize) -> FieldColumn<F> {
+        let zeros = |domain: GeneralEvaluationDomain<F>| {
+            Evaluations::from_vec_and_domain(vec![F::zero(); domain.size()], domain)
+        };
+        let mut col = FieldColumn {
+            poly: DensePolynomial::zero(),
+            evals: zeros(self.x1),
+            evals_4x: zeros(self.x4),
+            payload_len: 0,
+        };
+        self.add_to_cells(&mut col, &[(i, F::one())]);
+        col
+    }
+
+    // See `Domain::add_to_cells`.
+    fn add_to_cells(&self, col: &mut FieldColumn<F>, deltas: &[(usize, F)]) {
+        let x1 = self.x1;
+        let x4 = self.x4;
+        debug_assert_eq!(col.evals.domain(), x1);
+        let mut coeffs = col.poly.coeffs.clone();
+        coeffs.resize(x1.size(), F::zero());
+        // `X^N - 1` over `x4` takes `4` distinct values, see `vanishing_poly_inv_4x`.
+        let z: Vec<F> = (0..4)
+            .map(|k| x1.evaluate_vanishing_polynomial(x4.element(k)))
+            .collect();
+        for &(i, delta) in deltas {
+            col.evals.evals[i] += delta;
+            let wi = x1.element(i);
+            let wi_inv = wi.inverse().unwrap();
+            // `L_i(X) = 1/N.(1 + w^{-i}X + w^{-2i}X^2 + ... + w^{-(N-1)i}X^{N-1})`
+            let c = delta * x1.size_inv();
+            let mut ci = c;
+            for coeff in coeffs.iter_mut() {
+                *coeff += ci;
+                ci *= wi_inv;
+            }
+            // `L_i(X) = w^i/N.(X^N - 1)/(X - w^i)`, `x4` is a coset, so `X - w^i` doesn't vanish over it.
+            let mut denoms: Vec<F> = x4.elements().map(|x| x - wi).collect();
+            batch_inversion(&mut denoms);
+            let c = c * wi;
+            col.evals_4x
+                .evals
+                .iter_mut()
+                .zip(denoms)
+                .zip(z.iter().cycle())
+                .for_each(|((e, denom_inv), z)| *e += c * z * denom_inv);
+        }
+        col.poly = DensePolynomial::from_coefficients_vec(coeffs);
+    }
+
     // Amplifies the number of the evaluations of the polynomial so it can be multiplied in linear time.
     fn amplify(&self, poly: &DensePolynomial<F>) -> Evaluations<F> {
         poly.evaluate_over_domain_by_ref(self.x4)
@@ -60,10 +115,11 @@ pub struct Domain<F: FftField> {
     pub domains: Domains<F>,
     pub zk_rows: usize,
     pub capacity: usize,
-    pub not_last_row: FieldColumn<F>,
-    pub l_first: FieldColumn<F>,
-    pub l_last: FieldColumn<F>,
     zk_rows_prod: DensePolynomial<F>,
+    // `1 / Z(X)` over `x4`, where `Z(X)` is the vanishing polynomial of the first `capacity` rows.
+    vanishing_poly_inv_4x: Evaluations<F>,
+    l_first: FieldColumn<F>,
+    l_last: FieldColumn<F>,
     blinding: bool,
 }
 
@@ -80,24 +136,22 @@ impl<F: FftField> Domain<F> {
         let domains = Domains::new(n);
         let domain_size = domains.x1.size();
         let capacity = domain_size - zk_rows;
-        let last_row_index = capacity - 1;
 
-        let l_first = l_i(0, domain_size);
-        let l_first = domains.column_from_evals(l_first, 0);
-        let l_last = l_i(last_row_index, domain_size);
-        let l_last = domains.column_from_evals(l_last, 0);
+        let (zk_rows_prod, _) = compute_row_polys(domains.x1, zk_rows);
+        let vanishing_poly_inv_4x = vanishing_poly_inv_4x(&domains, &zk_rows_prod);
 
-        let (zk_rows_prod, last_row) = compute_row_polys(domains.x1, zk_rows);
-        let not_last_row = domains.column_from_poly(last_row);
+        // Built once per domain, as every gadget clones them for every proof.
+        let l_first = domains.l_i_column(0);
+        let l_last = domains.l_i_column(capacity - 1);
 
         Self {
             domains,
             zk_rows,
             capacity,
-            not_last_row,
+            zk_rows_prod,
+            vanishing_poly_inv_4x,
             l_first,
             l_last,
-            zk_rows_prod,
             blinding: zk_rows != 0,
         }
     }
@@ -133,6 +187,25 @@ impl<F: FftField> Domain<F> {
         r.is_zero().then_some(q)
     }
 
+    /// Computes the quotient polynomial from the evaluations of a constraint polynomial over the `4x` domain,
+    /// dividing them pointwise by the vanishing polynomial and interpolating the result.
+    /// Returns `None` if the constraint polynomial is not divisible by the vanishing polynomial.
+    ///
+    /// The constraint polynomial should be of degree less than `4N`.
+    pub fn divide_by_vanishing_poly_4x(
+        &self,
+        evals_4x: &Evaluations<F>,
+    ) -> Option<DensePolynomial<F>> {
+        debug_assert_eq!(evals_4x.domain(), self.domains.x4);
+        let quotient_4x = evals_4x * &self.vanishing_poly_inv_4x;
+        let quotient = quotient_4x.interpolate();
+        // The interpolated `q` satisfies `q.Z = C` over the `4N` points. If `deg(q) < 4N - deg(Z)`,
+        // then `q.Z` is of degree less than `4N`, so `q.Z = C` identically, and `C` is divisible by `Z`.
+        // Otherwise `C` is not divisible, as the actual quotient would be of degree `deg(C) - deg(Z) < 4N - deg(Z)`.
+        let z_degree = self.capacity;
+        (quotient.degree() < self.domains.x4.size() - z_degree).then_some(quotient)
+    }
+
     fn div_by_z_with_remainder(
         &self,
         p: &DensePolynomial<F>,
@@ -166,6 +239,44 @@ impl<F: FftField> Domain<F> {
         self._column(values, true)
     }
 
+    /// Recomputes the evaluations of a compacted column, see `FieldColumn::compact`.
+    pub fn expand(&self, col: &CompactColumn<F>) -> FieldColumn<F> {
+        assert_eq!(col.domain(), self.domains.x1, "column over another domain");
+        self.domains.column_from_compact(col)
+    }
+
+    /// The selector of the first row, `L_0(X)`.
+    pub fn l_first(&self) -> FieldColumn<F> {
+        self.l_first.clone()
+    }
+
+    /// The selector of the last row of the `capacity` rows, `L_{capacity-1}(X)`.
+    pub fn l_last(&self) -> FieldColumn<F> {
+        self.l_last.clone()
+    }
+
+    /// `X - w^{capacity-1}`, that vanishes only on the last row of the `capacity` rows.
+    pub fn not_last_row(&self) -> FieldColumn<F> {
+        let last_row = self.domains.x1.element(self.capacity - 1);
+        let evals = |domain: GeneralEvaluationDomain<F>| {
+            let evals = domain.elements().map(|x| x - last_row).collect();
+            Evaluations::from_vec_and_domain(evals, domain)
+        };
+        FieldColumn {
+            poly: z_poly(last_row),
+            evals: evals(self.domains.x1),
+            evals_4x: evals(self.domains.x4),
+            payload_len: self.domain_size(),
+        }
+    }
+
+    /// For each `(i, delta)` adds `delta` to the `i`-th cell of the column,
+    /// that is adds `delta.L_i(X)` to the column polynomial, where `L_i` is the `i`-th Lagrange basis polynomial,
+    /// updating the evaluations consistently in `O(N)` per cell.
+    pub fn add_to_cells(&self, col: &mut FieldColumn<F>, deltas: &[(usize, F)]) {
+        self.domains.add_to_cells(col, deltas)
+    }
+
     pub fn domain(&self) -> GeneralEvaluationDomain<F> {
         self.domains.x1
     }
@@ -183,12 +294,6 @@ impl<F: FftField> Domain<F> {
     }
 }
 
-fn l_i<F: FftField>(i: usize, n: usize) -> Vec<F> {
-    let mut l_i = vec![F::zero(); n];
-    l_i[i] = F::one();
-    l_i
-}
-
 /// For the generator `w = domain.group_gen()` of a domain of size `N`, returns `w^{N-1}, w^{N-2}, ..., w^0 = 1`.
 fn elements_rev<F: FftField, D: EvaluationDomain<F>>(domain: D) -> impl Iterator<Item = F> {
     let w_inv = domain.group_gen_inv();
@@ -220,6 +325,31 @@ fn compute_row_polys<F: FftField, D: EvaluationDomain<F>>(
     (zk_rows_prod, last_row)
 }
 
+/// Evaluates `Z(X) = (X^N - 1) / zk_rows_prod(X)` over the `4x` coset, and inverts the evaluations.
+fn vanishing_poly_inv_4x<F: FftField>(
+    domains: &Domains<F>,
+    zk_rows_prod: &DensePolynomial<F>,
+) -> Evaluations<F> {
+    // For `x = g.w^i`, where `w` is the generator of the `4N` subgroup,
+    // `x^N - 1 = g^N.(w^N)^i - 1` takes just `4` distinct values, as `w^N` is the 4th root of unity.
+    let mut z_inv: Vec<F> = (0..4)
+        .map(|i| {
+            domains
+                .x1
+                .evaluate_vanishing_polynomial(domains.x4.element(i))
+        })
+        .collect();
+    batch_inversion(&mut z_inv);
+    let mut evals = domains.amplify(zk_rows_prod);
+    evals
+        .evals
+        .iter_mut()
+        .zip(z_inv.iter().cycle())
+        .for_each(|(e, z_inv)| *e *= z_inv);
+    evals
+}
+
+#[derive(Clone)]
 pub struct EvaluatedDomain<F: FftField> {
     pub domain: GeneralEvaluationDomain<F>,
     pub not_last_row: F,
@@ -284,6 +414,11 @@ mod tests {
     use ark_poly::Radix2EvaluationDomain;
     use ark_std::{test_rng, UniformRand};
 
+    use crate::gadgets::fixed_cells::FixedCells;
+    use crate::test_helpers::random_vec;
+    use crate::Column;
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+
     fn _test_evaluated_domain(hiding: bool) {
         let rng = &mut test_rng();
 
@@ -292,10 +427,10 @@ mod tests {
         let domain = Domain::test_domain(n, hiding);
         let z = Fq::rand(rng);
         let domain_eval = domain.evaluate(z);
-        assert_eq!(domain.l_first.poly.evaluate(&z), domain_eval.l_first);
-        assert_eq!(domain.l_last.poly.evaluate(&z), domain_eval.l_last);
+        assert_eq!(domain.l_first().poly.evaluate(&z), domain_eval.l_first);
+        assert_eq!(domain.l_last().poly.evaluate(&z), domain_eval.l_last);
         assert_eq!(
-            domain.not_last_row.poly.evaluate(&z),
+            domain.not_last_row().poly.evaluate(&z),
             domain_eval.not_last_row
         );
     }
@@ -329,6 +464,115 @@ mod tests {
         compute_row_polys(domain, zk_rows);
     }
 
+    fn _test_quotient_over_coset(hiding: bool) {
+        let rng = &mut test_rng();
+
+        let n = 1024;
+        let domain = Domain::test_domain(n, hiding);
+        let col = domain.column(random_vec(domain.capacity, rng));
+        let constraint = &FixedCells::constraint_cell(
+            &col,
+            &domain.l_last(),
+            domain.capacity - 1,
+            col.evals[domain.capacity - 1],
+        );
+        let quotient = domain.divide_by_vanishing_poly_4x(constraint);
+        let expected = domain.compute_quotient(&constraint.interpolate_by_ref());
+        assert!(quotient.is_some());
+        assert_eq!(quotient, expected);
+
+        let constraint = &FixedCells::constraint_cell(
+            &col,
+            &domain.l_last(),
+            domain.capacity - 1,
+            col.evals[domain.capacity - 1] + Fq::one(),
+        );
+        assert!(domain.divide_by_vanishing_poly_4x(constraint).is_none());
+        assert!(domain
+            .compute_quotient(&constraint.interpolate_by_ref())
+            .is_none());
+    }
+
+    #[test]
+    fn test_quotient_over_coset() {
+        _test_quotient_over_coset(false);
+        _test_quotient_over_coset(true);
+    }
+
+    #[test]
+    fn test_selectors() {
+        let n = 16;
+        let domain = Domain::<Fq>::test_domain(n, true);
+        let unit_column = |i| {
+            let mut vals = vec![Fq::zero(); domain.capacity];
+            vals[i] = Fq::one();
+            domain.public_column(vals)
+        };
+        let (l_first, expected) = (domain.l_first(), unit_column(0));
+        assert_eq!(l_first.poly, expected.poly);
+        assert_eq!(l_first.evals, expected.evals);
+        assert_eq!(l_first.evals_4x, expected.evals_4x);
+        let (l_last, expected) = (domain.l_last(), unit_column(domain.capacity - 1));
+        assert_eq!(l_last.poly, expected.poly);
+        assert_eq!(l_last.evals, expected.evals);
+        assert_eq!(l_last.evals_4x, expected.evals_4x);
+
+        let not_last_row = domain.not_last_row();
+        let (_, last_row) = compute_row_polys(domain.domains.x1, domain.zk_rows);
+        assert_eq!(not_last_row.poly, last_row);
+        assert_eq!(
+            not_last_row.evals,
+            last_row.evaluate_over_domain_by_ref(domain.domains.x1)
+        );
+        assert_eq!(
+            not_last_row.evals_4x,
+            last_row.evaluate_over_domain_by_ref(domain.domains.x4)
+        );
+    }
+
+    #[test]
+    fn column_compaction() {
+        let rng = &mut test_rng();
+
+        let n = 16;
+        let domain = Domain::test_domain(n, true);
+        let col = domain.column(random_vec(domain.capacity - 1, rng));
+        let expanded = domain.expand(&col.compact());
+        assert_eq!(expanded.poly, col.poly);
+        assert_eq!(expanded.evals, col.evals);
+        assert_eq!(expanded.evals_4x, col.evals_4x);
+        assert_eq!(expanded.payload(), col.payload());
+
+        let mut bytes = vec![];
+        col.compact().serialize_compressed(&mut bytes).unwrap();
+        let compact = CompactColumn::<Fq>::deserialize_compressed(&bytes[..]).unwrap();
+        assert_eq!(domain.expand(&compact).evals, col.evals);
+        // The column doesn't fit a smaller domain.
+        let other_domain = Domain::<Fq>::test_domain(n / 2, true);
+        let mut short = vec![];
+        let mut compact = col.compact();
+        compact.domain = other_domain.domain();
+        compact.serialize_compressed(&mut short).unwrap();
+        assert!(CompactColumn::<Fq>::deserialize_compressed(&short[..]).is_err());
+    }
+
+    #[test]
+    fn test_add_to_cells() {
+        let rng = &mut test_rng();
+
+        let n = 16;
+        let domain = Domain::test_domain(n, true);
+        let mut vals: Vec<Fq> = random_vec(domain.capacity - 1, rng);
+        let mut col = domain.public_column(vals.clone());
+        let deltas = [(0, Fq::rand(rng)), (5, Fq::rand(rng)), (5, Fq::rand(rng))];
+        domain.add_to_cells(&mut col, &deltas);
+        deltas.iter().for_each(|&(i, delta)| vals[i] += delta);
+        let expected = domain.public_column(vals);
+        assert_eq!(col.poly, expected.poly);
+        assert_eq!(col.evals, expected.evals);
+        assert_eq!(col.evals_4x, expected.evals_4x);
+    }
+
     #[test]
     fn test_evaluated_domain() {
         _test_evaluated_domain(false);
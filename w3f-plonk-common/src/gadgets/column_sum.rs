@@ -44,7 +44,7 @@ impl<F: FftField> ColumnSumPolys<F> {
         Self {
             col,
             acc,
-            not_last: domain.not_last_row.clone(),
+            not_last: domain.not_last_row(),
         }
     }
 
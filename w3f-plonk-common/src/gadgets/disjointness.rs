@@ -0,0 +1,110 @@
+use ark_ff::{FftField, Field, Zero};
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::Evaluations;
+
+use ark_std::{vec, vec::Vec};
+
+use crate::gadgets::booleanity::BitColumn;
+use crate::gadgets::VerifierGadget;
+use crate::{const_evals, FieldColumn};
+
+/// Constrains the bit columns to have no set bits in common within the rows highlighted by the `selector`:
+/// `selector(X).s(X).(1 - s(X)) = 0` over the domain, where `s = bits_1 + ... + bits_k`.
+/// As the columns are binary, `s` is binary iff at most one of the bits is set in a row.
+pub struct Disjointness<F: FftField> {
+    bits: Vec<BitColumn<F>>,
+    selector: FieldColumn<F>,
+}
+
+pub struct DisjointnessValues<F: Field> {
+    pub bits: Vec<F>,
+    pub selector: F,
+}
+
+impl<F: FftField> Disjointness<F> {
+    pub fn init(bits: Vec<BitColumn<F>>, selector: FieldColumn<F>) -> Self {
+        assert!(!bits.is_empty());
+        Self { bits, selector }
+    }
+
+    pub fn constraints(&self) -> Vec<Evaluations<F>> {
+        let domain = self.bits[0].domain_4x();
+        let mut s = const_evals(F::zero(), domain);
+        for bits in &self.bits {
+            s += &bits.col.evals_4x; // s = b1 + ... + bk
+        }
+        let mut c = const_evals(F::one(), domain); // c = 1
+        c -= &s; // c = 1 - s
+        c *= &s; // c = s * (1 - s)
+        c *= &self.selector.evals_4x; // c = selector * s * (1 - s)
+        vec![c]
+    }
+
+    pub fn constraints_linearized(&self, _z: &F) -> Vec<DensePolynomial<F>> {
+        vec![DensePolynomial::zero()]
+    }
+}
+
+impl<F: Field> VerifierGadget<F> for DisjointnessValues<F> {
+    fn evaluate_constraints_main(&self) -> Vec<F> {
+        let s: F = self.bits.iter().sum();
+        let c = self.selector * s * (F::one() - s);
+        vec![c]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ed_on_bls12_381_bandersnatch::Fq;
+    use ark_ff::One;
+    use ark_poly::Polynomial;
+
+    use crate::domain::Domain;
+
+    use super::*;
+
+    fn _test_disjointness_gadget(hiding: bool) {
+        let log_n = 10;
+        let n = 2usize.pow(log_n);
+        let domain = Domain::test_domain(n, hiding);
+        let len = domain.capacity - 1;
+
+        let selector = domain.public_column(vec![Fq::one(); len / 2]);
+
+        let mut bits_1 = vec![false; len];
+        let mut bits_2 = vec![false; len];
+        bits_1[3] = true;
+        bits_2[5] = true;
+        // Outside of the selector.
+        bits_1[len - 1] = true;
+        bits_2[len - 1] = true;
+        let gadget = Disjointness::init(
+            vec![
+                BitColumn::init(bits_1.clone(), &domain),
+                BitColumn::init(bits_2.clone(), &domain),
+            ],
+            selector.clone(),
+        );
+        let constraint_poly = gadget.constraints()[0].interpolate_by_ref();
+        assert_eq!(constraint_poly.degree(), 3 * (n - 1));
+        assert!(domain.compute_quotient(&constraint_poly).is_some());
+
+        // Within the selector.
+        bits_1[5] = true;
+        let gadget = Disjointness::init(
+            vec![
+                BitColumn::init(bits_1, &domain),
+                BitColumn::init(bits_2, &domain),
+            ],
+            selector,
+        );
+        let constraint_poly = gadget.constraints()[0].interpolate_by_ref();
+        assert!(domain.compute_quotient(&constraint_poly).is_none());
+    }
+
+    #[test]
+    fn test_disjointness_gadget() {
+        _test_disjointness_gadget(false);
+        _test_disjointness_gadget(true);
+    }
+}
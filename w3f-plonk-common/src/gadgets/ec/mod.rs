@@ -1,10 +1,12 @@
 use crate::domain::Domain;
 use crate::gadgets::booleanity::BitColumn;
-use crate::{Column, FieldColumn};
+use crate::{Column, CompactColumn, FieldColumn};
 use ark_ec::{AffineRepr, CurveGroup};
 use ark_ff::{FftField, Field};
 use ark_poly::GeneralEvaluationDomain;
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+};
 use ark_std::marker::PhantomData;
 use ark_std::vec::Vec;
 
@@ -43,6 +45,95 @@ impl<F: FftField, P: AffineRepr<BaseField = F>> AffineColumn<F, P> {
     pub fn evaluate(&self, z: &F) -> (F, F) {
         (self.xs.evaluate(z), self.ys.evaluate(z))
     }
+
+    /// Replaces the points at the given indices, updating the coordinate columns in place.
+    pub fn update_points(&mut self, updates: &[(usize, P)], domain: &Domain<F>) {
+        let mut x_deltas = Vec::with_capacity(updates.len());
+        let mut y_deltas = Vec::with_capacity(updates.len());
+        for &(i, p) in updates {
+            assert!(!p.is_zero());
+            let (old_x, old_y) = self.points[i].xy().unwrap();
+            let (new_x, new_y) = p.xy().unwrap();
+            x_deltas.push((i, new_x - old_x));
+            y_deltas.push((i, new_y - old_y));
+            self.points[i] = p;
+        }
+        domain.add_to_cells(&mut self.xs, &x_deltas);
+        domain.add_to_cells(&mut self.ys, &y_deltas);
+    }
+
+    pub fn compact(&self) -> CompactAffineColumn<F, P> {
+        CompactAffineColumn {
+            points: self.points.clone(),
+            xs: self.xs.compact(),
+            ys: self.ys.compact(),
+        }
+    }
+}
+
+// `AffineColumn` with the coordinate columns compacted, see `CompactColumn`.
+#[derive(Clone, CanonicalSerialize)]
+pub struct CompactAffineColumn<F: FftField, P: AffineRepr<BaseField = F>> {
+    points: Vec<P>,
+    pub xs: CompactColumn<F>,
+    pub ys: CompactColumn<F>,
+}
+
+impl<F: FftField, P: AffineRepr<BaseField = F>> CompactAffineColumn<F, P> {
+    pub fn expand(&self, domain: &Domain<F>) -> AffineColumn<F, P> {
+        AffineColumn {
+            points: self.points.clone(),
+            xs: domain.expand(&self.xs),
+            ys: domain.expand(&self.ys),
+        }
+    }
+
+    pub fn points(&self) -> &[P] {
+        &self.points
+    }
+}
+
+// Checks that the coordinate columns interpolate the points, as in `AffineColumn::column`.
+impl<F: FftField, P: AffineRepr<BaseField = F>> Valid for CompactAffineColumn<F, P> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.points.check()?;
+        self.xs.check()?;
+        self.ys.check()?;
+        let domain = self.xs.domain();
+        let n = self.points.len();
+        if self.ys.domain() != domain || self.xs.payload_len() != n || self.ys.payload_len() != n {
+            return Err(SerializationError::InvalidData);
+        }
+        let xs = self.xs.poly.evaluate_over_domain_by_ref(domain);
+        let ys = self.ys.poly.evaluate_over_domain_by_ref(domain);
+        let interpolated = self
+            .points
+            .iter()
+            .zip(xs.evals.iter().zip(ys.evals.iter()))
+            .all(|(p, (x, y))| p.xy() == Some((*x, *y)));
+        if !interpolated {
+            return Err(SerializationError::InvalidData);
+        }
+        Ok(())
+    }
+}
+
+impl<F: FftField, P: AffineRepr<BaseField = F>> CanonicalDeserialize for CompactAffineColumn<F, P> {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let col = Self {
+            points: Vec::deserialize_with_mode(&mut reader, compress, validate)?,
+            xs: CompactColumn::deserialize_with_mode(&mut reader, compress, validate)?,
+            ys: CompactColumn::deserialize_with_mode(&mut reader, compress, validate)?,
+        };
+        if let Validate::Yes = validate {
+            col.check()?;
+        }
+        Ok(col)
+    }
 }
 
 impl<F: FftField, P: AffineRepr<BaseField = F>> Column<F, P> for AffineColumn<F, P> {
@@ -87,7 +178,7 @@ where
     ) -> Self {
         debug_assert_eq!(bitmask.payload_len(), domain.capacity - 1);
         debug_assert_eq!(points.payload_len(), domain.capacity - 1);
-        let not_last = domain.not_last_row.clone();
+        let not_last = domain.not_last_row();
         let mut projective_acc = seed.into_group();
         let projective_points: Vec<_> = bitmask
             .bits
@@ -31,7 +31,7 @@ where
     pub fn init(p: P, domain: &Domain<F>) -> Self {
         let doublings = Self::doublings_of(p, domain);
         let doublings = AffineColumn::public_column(doublings, domain);
-        let not_last = domain.not_last_row.clone();
+        let not_last = domain.not_last_row();
         Self {
             doublings,
             not_last,
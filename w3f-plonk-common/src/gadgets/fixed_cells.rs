@@ -34,8 +34,8 @@ impl<F: FftField> FixedCells<F> {
 
     pub fn init(col: FieldColumn<F>, domain: &Domain<F>, col_first: F, col_last: F) -> Self {
         debug_assert_eq!(col.payload_len(), domain.capacity);
-        let l_first = domain.l_first.clone();
-        let l_last = domain.l_last.clone();
+        let l_first = domain.l_first();
+        let l_last = domain.l_last();
         Self {
             col,
             l_first,
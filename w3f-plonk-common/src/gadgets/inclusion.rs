@@ -0,0 +1,101 @@
+use ark_ff::{FftField, Field, Zero};
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::Evaluations;
+
+use ark_std::{vec, vec::Vec};
+
+use crate::gadgets::booleanity::BitColumn;
+use crate::gadgets::VerifierGadget;
+use crate::{const_evals, Column, FieldColumn};
+
+/// Constrains the bits set in the rows highlighted by the `superset` selector
+/// to the rows highlighted by the `subset` selector:
+/// `bits(X).superset(X).(1 - subset(X)) = 0` over the domain.
+pub struct Inclusion<F: FftField> {
+    bits: BitColumn<F>,
+    superset: FieldColumn<F>,
+    subset: FieldColumn<F>,
+}
+
+pub struct InclusionValues<F: Field> {
+    pub bits: F,
+    pub superset: F,
+    pub subset: F,
+}
+
+impl<F: FftField> Inclusion<F> {
+    pub fn init(bits: BitColumn<F>, superset: FieldColumn<F>, subset: FieldColumn<F>) -> Self {
+        Self {
+            bits,
+            superset,
+            subset,
+        }
+    }
+
+    pub fn constraints(&self) -> Vec<Evaluations<F>> {
+        let mut c = const_evals(F::one(), self.bits.domain_4x()); // c = 1
+        c -= &self.subset.evals_4x; // c = 1 - subset
+        c *= &self.superset.evals_4x; // c = superset * (1 - subset)
+        c *= &self.bits.col.evals_4x; // c = bits * superset * (1 - subset)
+        vec![c]
+    }
+
+    pub fn constraints_linearized(&self, _z: &F) -> Vec<DensePolynomial<F>> {
+        vec![DensePolynomial::zero()]
+    }
+}
+
+impl<F: Field> VerifierGadget<F> for InclusionValues<F> {
+    fn evaluate_constraints_main(&self) -> Vec<F> {
+        let c = self.bits * self.superset * (F::one() - self.subset);
+        vec![c]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ed_on_bls12_381_bandersnatch::Fq;
+    use ark_ff::One;
+    use ark_poly::Polynomial;
+
+    use crate::domain::Domain;
+
+    use super::*;
+
+    fn _test_inclusion_gadget(hiding: bool) {
+        let log_n = 10;
+        let n = 2usize.pow(log_n);
+        let domain = Domain::test_domain(n, hiding);
+        let len = domain.capacity - 1;
+
+        let superset = domain.public_column(vec![Fq::one(); len / 2]);
+        let mut subset = vec![Fq::zero(); len];
+        subset[10..20].iter_mut().for_each(|s| *s = Fq::one());
+        let subset = domain.public_column(subset);
+
+        let mut bits = vec![false; len];
+        bits[15] = true;
+        // Outside of the superset.
+        bits[len - 1] = true;
+        let gadget = Inclusion::init(
+            BitColumn::init(bits.clone(), &domain),
+            superset.clone(),
+            subset.clone(),
+        );
+        let constraint_poly = gadget.constraints()[0].interpolate_by_ref();
+        assert_eq!(constraint_poly.degree(), 3 * (n - 1));
+        assert!(domain.compute_quotient(&constraint_poly).is_some());
+
+        // Within the superset, but outside of the subset.
+        bits[5] = true;
+        let gadget = Inclusion::init(BitColumn::init(bits, &domain), superset, subset);
+        let constraint_poly = gadget.constraints()[0].interpolate_by_ref();
+        assert!(domain.compute_quotient(&constraint_poly).is_none());
+    }
+
+    #[test]
+    fn test_inclusion_gadget() {
+        _test_inclusion_gadget(false);
+        _test_inclusion_gadget(true);
+    }
+}
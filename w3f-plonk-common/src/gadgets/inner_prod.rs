@@ -34,7 +34,7 @@ impl<F: FftField> InnerProd<F> {
         Self {
             a,
             b,
-            not_last: domain.not_last_row.clone(),
+            not_last: domain.not_last_row(),
             acc,
         }
     }
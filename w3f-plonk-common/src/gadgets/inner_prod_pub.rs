@@ -27,7 +27,7 @@ impl<F: FftField> InnerProd<F> {
     pub fn init(a: FieldColumn<F>, b: FieldColumn<F>, domain: &Domain<F>) -> Self {
         assert_eq!(a.evals.evals.len(), domain.capacity);
         assert_eq!(b.evals.evals.len(), domain.capacity);
-        let l_last = domain.l_last.clone();
+        let l_last = domain.l_last();
         let inner_prods = Self::partial_inner_prods(&a.evals.evals, &b.evals.evals);
         let (&inner_prod, partial_prods) = inner_prods.split_last().unwrap();
         // 0, a[0]b[0], a[0]b[0] + a[1]b[1], ..., a[0]b[0] + a[1]b[1] + ... + a[n-2]b[n-2]
@@ -6,9 +6,12 @@ use ark_std::vec::Vec;
 pub mod booleanity;
 // pub mod inner_prod_pub;
 pub mod column_sum;
+pub mod disjointness;
 pub mod ec;
 pub mod fixed_cells;
+pub mod inclusion;
 pub mod inner_prod;
+pub mod range_check;
 
 pub trait ProverGadget<F: FftField> {
     // Columns populated by the gadget.
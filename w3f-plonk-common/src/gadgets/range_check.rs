@@ -0,0 +1,197 @@
+use ark_ff::{BigInteger, FftField, Field, PrimeField, Zero};
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::{Evaluations, GeneralEvaluationDomain};
+
+use ark_std::{vec, vec::Vec};
+
+use crate::domain::Domain;
+use crate::gadgets::booleanity::{BitColumn, Booleanity};
+use crate::gadgets::fixed_cells::{FixedCells, FixedCellsValues};
+use crate::gadgets::inner_prod::InnerProd;
+use crate::gadgets::{ProverGadget, VerifierGadget};
+use crate::{const_evals, Column, FieldColumn};
+
+/// Returns the public column `1, 2, 4, ..., 2^(bitlen-1), 0, ..., 0`,
+/// used by `RangeCheck` to recompose `bitlen`-bit values.
+pub fn powers_of_2_column<F: FftField>(bitlen: usize, domain: &Domain<F>) -> FieldColumn<F> {
+    assert!(bitlen < domain.capacity);
+    let mut powers = Vec::with_capacity(domain.capacity - 1);
+    let mut power = F::one();
+    for _ in 0..bitlen {
+        powers.push(power);
+        power.double_in_place();
+    }
+    powers.resize(domain.capacity - 1, F::zero());
+    domain.public_column(powers)
+}
+
+/// Checks that the value in the last cell of the column `col` lies in `[lower, lower + 2^bitlen)`.
+///
+/// The difference `d = col[last] - lower` is decomposed into `bitlen` bits placed in the first cells of the `bits` column,
+/// then `acc` accumulates the inner product of `bits` with the public `powers_of_2_column`, starting from `0`,
+/// and `acc[last] = d` is enforced. As the powers are `0` past the first `bitlen` cells, `acc[last]` is in range.
+/// `2^bitlen` should be much smaller than the field modulus.
+pub struct RangeCheck<F: FftField> {
+    col: FieldColumn<F>,
+    lower: F,
+    bits: BitColumn<F>,
+    booleanity: Booleanity<F>,
+    inner_prod: InnerProd<F>,
+    l_first: FieldColumn<F>,
+    l_last: FieldColumn<F>,
+}
+
+pub struct RangeCheckValues<F: Field> {
+    pub col: F,
+    pub lower: F,
+    pub bits: F,
+    pub powers_of_2: F,
+    pub acc: F,
+    pub not_last: F,
+    pub l_first: F,
+    pub l_last: F,
+}
+
+impl<F: PrimeField> RangeCheck<F> {
+    pub fn init(
+        col: FieldColumn<F>,
+        lower: F,
+        powers_of_2: FieldColumn<F>,
+        bitlen: usize,
+        domain: &Domain<F>,
+    ) -> Self {
+        let diff = col.evals[domain.capacity - 1] - lower;
+        let mut bits = diff.into_bigint().to_bits_le();
+        bits.truncate(bitlen);
+        bits.resize(domain.capacity - 1, false);
+        let bits = BitColumn::init(bits, domain);
+        let booleanity = Booleanity::init(bits.clone());
+        let inner_prod = InnerProd::init(powers_of_2, bits.col.clone(), domain);
+        Self {
+            col,
+            lower,
+            bits,
+            booleanity,
+            inner_prod,
+            l_first: domain.l_first(),
+            l_last: domain.l_last(),
+        }
+    }
+}
+
+impl<F: FftField> RangeCheck<F> {
+    pub fn bits(&self) -> &BitColumn<F> {
+        &self.bits
+    }
+
+    pub fn acc(&self) -> &FieldColumn<F> {
+        &self.inner_prod.acc
+    }
+}
+
+impl<F: FftField> ProverGadget<F> for RangeCheck<F> {
+    fn witness_columns(&self) -> Vec<DensePolynomial<F>> {
+        vec![
+            self.bits.as_poly().clone(),
+            self.inner_prod.acc.poly.clone(),
+        ]
+    }
+
+    fn constraints(&self) -> Vec<Evaluations<F>> {
+        let acc = &self.inner_prod.acc;
+        let domain = self.bits.domain_4x();
+        let mut c = self.col.evals_4x.clone(); // c = col
+        c -= &acc.evals_4x; // c = col - acc
+        c -= &const_evals(self.lower, domain); // c = col - acc - lower
+        c *= &self.l_last.evals_4x; // c = L_last * (col - acc - lower)
+        let mut constraints = self.booleanity.constraints();
+        constraints.extend(self.inner_prod.constraints());
+        constraints.push(FixedCells::constraint_cell(
+            acc,
+            &self.l_first,
+            0,
+            F::zero(),
+        ));
+        constraints.push(c);
+        constraints
+    }
+
+    fn constraints_linearized(&self, z: &F) -> Vec<DensePolynomial<F>> {
+        let mut constraints = self.booleanity.constraints_linearized(z);
+        constraints.extend(self.inner_prod.constraints_linearized(z));
+        constraints.extend([DensePolynomial::zero(), DensePolynomial::zero()]);
+        constraints
+    }
+
+    fn domain(&self) -> GeneralEvaluationDomain<F> {
+        self.inner_prod.domain()
+    }
+}
+
+impl<F: Field> VerifierGadget<F> for RangeCheckValues<F> {
+    fn evaluate_constraints_main(&self) -> Vec<F> {
+        let booleanity = self.bits * (F::one() - self.bits);
+        let inner_prod = (-self.acc - self.powers_of_2 * self.bits) * self.not_last;
+        let acc_first = FixedCellsValues::evaluate_for_cell(self.acc, self.l_first, F::zero());
+        let acc_last = self.l_last * (self.col - self.acc - self.lower);
+        vec![booleanity, inner_prod, acc_first, acc_last]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_ed_on_bls12_381_bandersnatch::Fq;
+    use ark_poly::Polynomial;
+
+    use crate::domain::Domain;
+
+    use super::*;
+
+    fn range_check(value: u64, lower: u64, bitlen: usize, domain: &Domain<Fq>) -> RangeCheck<Fq> {
+        let mut col = vec![Fq::zero(); domain.capacity];
+        col[domain.capacity - 1] = Fq::from(value);
+        let col = domain.column(col);
+        let powers_of_2 = powers_of_2_column(bitlen, domain);
+        RangeCheck::init(col, Fq::from(lower), powers_of_2, bitlen, domain)
+    }
+
+    fn is_satisfied(gadget: &RangeCheck<Fq>, domain: &Domain<Fq>) -> bool {
+        gadget
+            .constraints()
+            .iter()
+            .all(|c| domain.compute_quotient(&c.interpolate_by_ref()).is_some())
+    }
+
+    fn _test_range_check_gadget(hiding: bool) {
+        let log_n = 10;
+        let n = 2usize.pow(log_n);
+        let domain = Domain::test_domain(n, hiding);
+        let bitlen = 64;
+
+        let gadget = range_check(1000, 1000, bitlen, &domain);
+        assert!(gadget.acc().evals.evals[0].is_zero());
+        let constraints = gadget.constraints();
+        assert_eq!(constraints.len(), 4);
+        assert_eq!(constraints[1].interpolate_by_ref().degree(), 2 * n - 1);
+        assert!(is_satisfied(&gadget, &domain));
+        assert!(is_satisfied(
+            &range_check(u64::MAX, 0, bitlen, &domain),
+            &domain
+        ));
+        assert!(is_satisfied(
+            &range_check(1001, 1000, bitlen, &domain),
+            &domain
+        ));
+        assert!(!is_satisfied(
+            &range_check(999, 1000, bitlen, &domain),
+            &domain
+        ));
+        assert!(!is_satisfied(&range_check(256, 0, 8, &domain), &domain));
+    }
+
+    #[test]
+    fn test_range_check_gadget() {
+        _test_range_check_gadget(false);
+        _test_range_check_gadget(true);
+    }
+}
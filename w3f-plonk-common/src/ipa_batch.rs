@@ -0,0 +1,72 @@
+use crate::piop::VerifierPiop;
+use crate::transcript::PlonkTranscript;
+use crate::verifier::{Challenges, OpeningClaims, PlonkVerifier};
+use crate::{ColumnsCommited, ColumnsEvaluated, Proof};
+use ark_ec::CurveGroup;
+use ark_std::rand::Rng;
+use w3f_pcs::pcs::ipa::IPA;
+use w3f_pcs::pcs::PCS;
+
+// Batches the IPA opening claims of many Plonk proofs, to be checked with a single call to `IPA::batch_verify`.
+//
+// Every proof is reduced to `2` opening claims (see `PlonkVerifier::opening_claims`).
+// The claims of proofs of different PIOPs can be batched together, as long as they share the IPA key.
+//
+// This is not an accumulation scheme: the batch keeps all the claims, and doesn't defer the `O(n)` checks
+// of the folded bases `G` to a single MSM, so the final check costs as much as `IPA::batch_verify` on all the claims.
+
+pub struct IpaBatch<C: CurveGroup> {
+    claims: OpeningClaims<C::ScalarField, IPA<C>>,
+    ipa_vk: <IPA<C> as PCS<C::ScalarField>>::VK,
+}
+
+impl<C: CurveGroup> IpaBatch<C> {
+    pub fn new(ipa_vk: <IPA<C> as PCS<C::ScalarField>>::VK) -> Self {
+        Self {
+            claims: OpeningClaims::empty(),
+            ipa_vk,
+        }
+    }
+
+    /// Adds the openings attesting the Plonk proof.
+    pub fn push<Piop, Commitments, Evaluations, T>(
+        &mut self,
+        verifier: &PlonkVerifier<C::ScalarField, IPA<C>, T>,
+        piop: Piop,
+        proof: Proof<C::ScalarField, IPA<C>, Commitments, Evaluations>,
+        challenges: Challenges<C::ScalarField>,
+    ) where
+        Piop: VerifierPiop<C::ScalarField, <IPA<C> as PCS<C::ScalarField>>::C>,
+        Commitments: ColumnsCommited<C::ScalarField, <IPA<C> as PCS<C::ScalarField>>::C>,
+        Evaluations: ColumnsEvaluated<C::ScalarField>,
+        T: PlonkTranscript<C::ScalarField, IPA<C>>,
+    {
+        self.push_claims(verifier.opening_claims(piop, proof, challenges));
+    }
+
+    /// Adds arbitrary opening claims, e.g. from proofs of other PIOPs over the same commitment key.
+    pub fn push_claims(&mut self, claims: OpeningClaims<C::ScalarField, IPA<C>>) {
+        self.claims.extend(claims);
+    }
+
+    /// Number of the openings in the batch.
+    pub fn len(&self) -> usize {
+        self.claims.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.claims.is_empty()
+    }
+
+    /// Verifies all the openings in the batch at once.
+    /// The `rng` should be unpredictable to the provers, e.g. derived from all the proofs in the batch.
+    pub fn verify<R: Rng>(self, rng: &mut R) -> bool {
+        let OpeningClaims {
+            commitments,
+            points,
+            values,
+            proofs,
+        } = self.claims;
+        IPA::<C>::batch_verify(&self.ipa_vk, commitments, points, values, proofs, rng).is_ok()
+    }
+}
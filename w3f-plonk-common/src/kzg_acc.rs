@@ -2,8 +2,13 @@ use crate::piop::VerifierPiop;
 use crate::verifier::Challenges;
 use crate::{ColumnsCommited, ColumnsEvaluated, Proof};
 use ark_ec::pairing::Pairing;
-use ark_ec::{CurveGroup, VariableBaseMSM};
+use ark_ec::{AffineRepr, CurveGroup, VariableBaseMSM};
 use ark_ff::{PrimeField, Zero};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+    Write,
+};
+use ark_std::ops::Range;
 use ark_std::rand::Rng;
 use ark_std::vec;
 use ark_std::vec::Vec;
@@ -25,9 +30,21 @@ pub struct KzgAccumulator<E: Pairing> {
     acc_scalars: Vec<E::ScalarField>,
     kzg_proofs: Vec<E::G1Affine>,
     randomizers: Vec<E::ScalarField>,
+    // The parts of the accumulator contributed by the individual proofs, in the order of accumulation.
+    items: Vec<AccumulatedItem<E::ScalarField>>,
     kzg_vk: KzgVerifierKey<E>,
 }
 
+// Locates the terms contributed by a proof in the accumulator.
+struct AccumulatedItem<F: PrimeField> {
+    // The range of `acc_points` and `acc_scalars`.
+    acc_terms: Range<usize>,
+    // The contribution to `acc_scalars[0]`.
+    g1_scalar: F,
+    // The range of `kzg_proofs` and `randomizers`.
+    proofs: Range<usize>,
+}
+
 impl<E: Pairing> KzgAccumulator<E> {
     pub fn new(kzg_vk: KzgVerifierKey<E>) -> Self {
         //TODO: capacity
@@ -36,6 +53,7 @@ impl<E: Pairing> KzgAccumulator<E> {
             acc_scalars: vec![E::ScalarField::zero()],
             kzg_proofs: vec![],
             randomizers: vec![],
+            items: vec![],
             kzg_vk,
         }
     }
@@ -78,6 +96,8 @@ impl<E: Pairing> KzgAccumulator<E> {
     {
         let r = F::rand(rng);
         let r2 = r.square();
+        let acc_terms_start = self.acc_points.len();
+        let proofs_start = self.kzg_proofs.len();
         let zeta = challenges.zeta;
 
         // TODO: it could be a method unless `to_vec(self)`
@@ -115,7 +135,7 @@ impl<E: Pairing> KzgAccumulator<E> {
 
         self.acc_points.push(proof.agg_at_zeta_proof);
         self.acc_scalars.push(zeta * r);
-        self.acc_scalars[0] -= agg_at_zeta * r;
+        let mut g1_scalar = -agg_at_zeta * r;
 
         // Openning at `z.w`
         // TODO: see above
@@ -125,25 +145,276 @@ impl<E: Pairing> KzgAccumulator<E> {
             .extend(lin_comm.0.into_iter().map(|c| c * r2).collect::<Vec<_>>());
         self.acc_points.push(proof.lin_at_zeta_omega_proof);
         self.acc_scalars.push(zeta_omega * r2);
-        self.acc_scalars[0] -= proof.lin_at_zeta_omega * r2;
+        g1_scalar -= proof.lin_at_zeta_omega * r2;
+        self.acc_scalars[0] += g1_scalar;
 
         self.kzg_proofs.push(proof.agg_at_zeta_proof);
         self.kzg_proofs.push(proof.lin_at_zeta_omega_proof);
         self.randomizers.push(r);
         self.randomizers.push(r2);
+
+        self.items.push(AccumulatedItem {
+            acc_terms: acc_terms_start..self.acc_points.len(),
+            g1_scalar,
+            proofs: proofs_start..self.kzg_proofs.len(),
+        });
+    }
+
+    /// Merges the accumulator of other proofs, to be verified with the same key, into this one.
+    /// The result is the same as if the proofs were accumulated into this accumulator, after the proofs already in it.
+    /// Accumulators built on different threads or machines can be merged, as long as the randomizers are independent.
+    ///
+    /// Fails if the accumulators were built with different keys.
+    pub fn merge(&mut self, other: Self) -> Result<(), ()> {
+        if self.acc_points[0] != other.acc_points[0] {
+            return Err(());
+        }
+        // `other.acc_points[0] = G1` is shared.
+        let acc_terms_offset = self.acc_points.len() - 1;
+        let proofs_offset = self.kzg_proofs.len();
+        self.acc_scalars[0] += other.acc_scalars[0];
+        self.acc_points.extend(other.acc_points.into_iter().skip(1));
+        self.acc_scalars
+            .extend(other.acc_scalars.into_iter().skip(1));
+        self.kzg_proofs.extend(other.kzg_proofs);
+        self.randomizers.extend(other.randomizers);
+        self.items
+            .extend(other.items.into_iter().map(|item| AccumulatedItem {
+                acc_terms: item.acc_terms.start + acc_terms_offset
+                    ..item.acc_terms.end + acc_terms_offset,
+                g1_scalar: item.g1_scalar,
+                proofs: item.proofs.start + proofs_offset..item.proofs.end + proofs_offset,
+            }));
+        Ok(())
+    }
+
+    pub fn kzg_vk(&self) -> &KzgVerifierKey<E> {
+        &self.kzg_vk
+    }
+
+    /// Number of the proofs accumulated.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
     }
 
     pub fn verify(&self) -> bool {
-        let proof = E::G1::msm(&self.kzg_proofs, &self.randomizers)
-            .unwrap()
-            .into_affine();
-        if !crate::is_in_correct_subgroup_assuming_on_curve::<E>(&proof) {
+        Self::verify_finalized(self.finalize(), &self.kzg_vk)
+    }
+
+    /// Collapses the accumulator to a single KZG opening, with `2` MSMs.
+    /// The openings finalized by different parties with independent randomness can be added up,
+    /// and then checked with a single pairing check, see `verify_finalized`.
+    pub fn finalize(&self) -> AccumulatedOpening<E> {
+        let proof = E::G1::msm(&self.kzg_proofs, &self.randomizers).unwrap();
+        let acc = E::G1::msm(&self.acc_points, &self.acc_scalars).unwrap();
+        Self::opening(acc, proof)
+    }
+
+    /// Checks the finalized accumulator with a single pairing check.
+    pub fn verify_finalized(opening: AccumulatedOpening<E>, kzg_vk: &KzgVerifierKey<E>) -> bool {
+        if !crate::is_in_correct_subgroup_assuming_on_curve::<E>(&opening.proof) {
             return false;
         }
-        let acc = (-E::G1::msm(&self.acc_points, &self.acc_scalars).unwrap()).into_affine();
-        if !crate::is_in_correct_subgroup_assuming_on_curve::<E>(&acc) {
+        if !crate::is_in_correct_subgroup_assuming_on_curve::<E>(&opening.acc) {
             return false;
         }
-        KZG::<E>::verify_accumulated(AccumulatedOpening { acc, proof }, &self.kzg_vk)
+        KZG::<E>::verify_accumulated(opening, kzg_vk)
+    }
+
+    /// Returns the indices, in the order of accumulation, of the proofs that fail verification.
+    /// The result is empty iff `verify` succeeds.
+    ///
+    /// Bisects the set of the proofs: the parts of the accumulator contributed by a subset are summed up
+    /// and checked with a single pairing check, and the failing subsets are halved until the failing proofs are found.
+    /// For `f` failing proofs out of `n` that takes `O(f.log(n))` pairings.
+    /// The contributions of the individual proofs are computed once, only if the batch fails.
+    pub fn verify_and_identify(&self) -> Vec<usize> {
+        let mut failed = vec![];
+        if self.verify() {
+            return failed;
+        }
+        let g1 = self.acc_points[0].into_group();
+        let parts: Vec<(E::G1, E::G1)> = self
+            .items
+            .iter()
+            .map(|item| {
+                let acc = g1 * item.g1_scalar
+                    + E::G1::msm(
+                        &self.acc_points[item.acc_terms.clone()],
+                        &self.acc_scalars[item.acc_terms.clone()],
+                    )
+                    .unwrap();
+                let proof = E::G1::msm(
+                    &self.kzg_proofs[item.proofs.clone()],
+                    &self.randomizers[item.proofs.clone()],
+                )
+                .unwrap();
+                (acc, proof)
+            })
+            .collect();
+        self.bisect(&parts, 0, &mut failed);
+        failed
+    }
+
+    // Collects the indices of the failing proofs among `parts`, which start at the index `offset`.
+    fn bisect(&self, parts: &[(E::G1, E::G1)], offset: usize, failed: &mut Vec<usize>) {
+        if parts.is_empty() {
+            return;
+        }
+        let (acc, proof) = parts
+            .iter()
+            .fold((E::G1::zero(), E::G1::zero()), |(acc, proof), part| {
+                (acc + part.0, proof + part.1)
+            });
+        if self.verify_parts(acc, proof) {
+            return;
+        }
+        if parts.len() == 1 {
+            failed.push(offset);
+            return;
+        }
+        let mid = parts.len() / 2;
+        self.bisect(&parts[..mid], offset, failed);
+        self.bisect(&parts[mid..], offset + mid, failed);
+    }
+
+    fn verify_parts(&self, acc: E::G1, proof: E::G1) -> bool {
+        Self::verify_finalized(Self::opening(acc, proof), &self.kzg_vk)
+    }
+
+    fn opening(acc: E::G1, proof: E::G1) -> AccumulatedOpening<E> {
+        let (acc, proof) = {
+            let affine = E::G1::normalize_batch(&[-acc, proof]);
+            (affine[0], affine[1])
+        };
+        AccumulatedOpening { acc, proof }
+    }
+
+    /// Deserializes an accumulator serialized with `CanonicalSerialize`, that doesn't include the verification key.
+    ///
+    /// A deserialized accumulator is only as trusted as the party that built it:
+    /// an accumulator is a linear combination of the openings, that can be faked to verify.
+    pub fn deserialize_with_vk<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+        kzg_vk: KzgVerifierKey<E>,
+    ) -> Result<Self, SerializationError> {
+        let acc_points = Vec::deserialize_with_mode(&mut reader, compress, validate)?;
+        let acc_scalars = Vec::deserialize_with_mode(&mut reader, compress, validate)?;
+        let kzg_proofs = Vec::deserialize_with_mode(&mut reader, compress, validate)?;
+        let randomizers = Vec::deserialize_with_mode(&mut reader, compress, validate)?;
+        let items = Vec::deserialize_with_mode(&mut reader, compress, validate)?;
+        let acc = Self {
+            acc_points,
+            acc_scalars,
+            kzg_proofs,
+            randomizers,
+            items,
+            kzg_vk,
+        };
+        if !acc.is_well_formed() {
+            return Err(SerializationError::InvalidData);
+        }
+        Ok(acc)
+    }
+
+    // The items should follow each other in the order of accumulation, without overlaps,
+    // and never cover the shared `G1` term at index `0`.
+    fn is_well_formed(&self) -> bool {
+        let mut acc_terms_end = 1;
+        let mut proofs_end = 0;
+        self.acc_points.first() == Some(&self.kzg_vk.g1)
+            && self.acc_points.len() == self.acc_scalars.len()
+            && self.kzg_proofs.len() == self.randomizers.len()
+            && self.items.iter().all(|item| {
+                let ok = acc_terms_end <= item.acc_terms.start
+                    && item.acc_terms.start <= item.acc_terms.end
+                    && proofs_end <= item.proofs.start
+                    && item.proofs.start <= item.proofs.end;
+                acc_terms_end = item.acc_terms.end;
+                proofs_end = item.proofs.end;
+                ok
+            })
+            && acc_terms_end <= self.acc_points.len()
+            && proofs_end <= self.kzg_proofs.len()
+    }
+}
+
+/// Serializes the accumulated openings, without the verification key, see `KzgAccumulator::deserialize_with_vk`.
+impl<E: Pairing> CanonicalSerialize for KzgAccumulator<E> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.acc_points.serialize_with_mode(&mut writer, compress)?;
+        self.acc_scalars
+            .serialize_with_mode(&mut writer, compress)?;
+        self.kzg_proofs.serialize_with_mode(&mut writer, compress)?;
+        self.randomizers
+            .serialize_with_mode(&mut writer, compress)?;
+        self.items.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.acc_points.serialized_size(compress)
+            + self.acc_scalars.serialized_size(compress)
+            + self.kzg_proofs.serialized_size(compress)
+            + self.randomizers.serialized_size(compress)
+            + self.items.serialized_size(compress)
+    }
+}
+
+impl<F: PrimeField> CanonicalSerialize for AccumulatedItem<F> {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.acc_terms
+            .start
+            .serialize_with_mode(&mut writer, compress)?;
+        self.acc_terms
+            .end
+            .serialize_with_mode(&mut writer, compress)?;
+        self.g1_scalar.serialize_with_mode(&mut writer, compress)?;
+        self.proofs
+            .start
+            .serialize_with_mode(&mut writer, compress)?;
+        self.proofs.end.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        4 * 0usize.serialized_size(compress) + self.g1_scalar.serialized_size(compress)
+    }
+}
+
+impl<F: PrimeField> Valid for AccumulatedItem<F> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.g1_scalar.check()
+    }
+}
+
+impl<F: PrimeField> CanonicalDeserialize for AccumulatedItem<F> {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let acc_terms_start = usize::deserialize_with_mode(&mut reader, compress, validate)?;
+        let acc_terms_end = usize::deserialize_with_mode(&mut reader, compress, validate)?;
+        let g1_scalar = F::deserialize_with_mode(&mut reader, compress, validate)?;
+        let proofs_start = usize::deserialize_with_mode(&mut reader, compress, validate)?;
+        let proofs_end = usize::deserialize_with_mode(&mut reader, compress, validate)?;
+        Ok(Self {
+            acc_terms: acc_terms_start..acc_terms_end,
+            g1_scalar,
+            proofs: proofs_start..proofs_end,
+        })
     }
 }
@@ -5,12 +5,16 @@ use ark_ec::AffineRepr;
 use ark_ff::{FftField, Field, PrimeField, Zero};
 use ark_poly::univariate::DensePolynomial;
 use ark_poly::{EvaluationDomain, Evaluations, GeneralEvaluationDomain, Polynomial};
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+};
 use ark_std::{vec, vec::Vec};
 use w3f_pcs::pcs::{Commitment, PCS};
 
+pub mod batch_verifier;
 pub mod domain;
 pub mod gadgets;
+pub mod ipa_batch;
 pub mod kzg_acc;
 pub mod piop;
 pub mod prover;
@@ -68,6 +72,69 @@ impl<F: FftField> Column<F, F> for FieldColumn<F> {
     }
 }
 
+/// `FieldColumn` stripped down to the coefficients of the column polynomial.
+/// Takes `N` field elements instead of `6N`, the evaluations are recomputed with `Domain::expand`.
+#[derive(Clone, CanonicalSerialize)]
+pub struct CompactColumn<F: FftField> {
+    pub poly: DensePolynomial<F>,
+    domain: GeneralEvaluationDomain<F>,
+    payload_len: usize,
+}
+
+impl<F: FftField> FieldColumn<F> {
+    pub fn compact(&self) -> CompactColumn<F> {
+        CompactColumn {
+            poly: self.poly.clone(),
+            domain: self.domain(),
+            payload_len: self.payload_len,
+        }
+    }
+}
+
+impl<F: FftField> CompactColumn<F> {
+    pub fn domain(&self) -> GeneralEvaluationDomain<F> {
+        self.domain
+    }
+
+    pub fn payload_len(&self) -> usize {
+        self.payload_len
+    }
+}
+
+// Checks that the column fits the domain, as `Domain::expand` expects.
+impl<F: FftField> Valid for CompactColumn<F> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.poly.check()?;
+        let n = self.domain.size();
+        if self.poly.coeffs.len() > n || self.payload_len > n {
+            return Err(SerializationError::InvalidData);
+        }
+        Ok(())
+    }
+}
+
+impl<F: FftField> CanonicalDeserialize for CompactColumn<F> {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let col = Self {
+            poly: DensePolynomial::deserialize_with_mode(&mut reader, compress, Validate::No)?,
+            domain: GeneralEvaluationDomain::deserialize_with_mode(
+                &mut reader,
+                compress,
+                validate,
+            )?,
+            payload_len: usize::deserialize_with_mode(&mut reader, compress, validate)?,
+        };
+        if let Validate::Yes = validate {
+            col.check()?;
+        }
+        Ok(col)
+    }
+}
+
 pub fn const_evals<F: FftField>(c: F, domain: GeneralEvaluationDomain<F>) -> Evaluations<F> {
     Evaluations::from_vec_and_domain(vec![c; domain.size()], domain)
 }
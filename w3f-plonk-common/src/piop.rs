@@ -32,13 +32,13 @@ pub trait ProverPiop<F: PrimeField, C: Commitment<F>> {
     // Constraint polynomials in evaluation form.
     fn constraints(&self) -> Vec<Evaluations<F>>;
 
+    // Returns `None` if the aggregated constraint is not satisfied, see `constraints_satisfied` to find which one.
     fn compute_quotient(&self, alphas: &[F]) -> Option<DensePolynomial<F>> {
         let constraints = self.constraints();
-        // Aggregate constraint polynomials in evaluation form...
+        // Aggregate constraint polynomials in evaluation form over the `4x` coset...
         let agg_constraint = aggregate_evaluations(&constraints, &alphas);
-        // ...and then interpolate (to save some FFTs).
-        let agg_constraint = agg_constraint.interpolate();
-        self.domain().compute_quotient(&agg_constraint)
+        // ...divide by the vanishing polynomial pointwise, and then interpolate once.
+        self.domain().divide_by_vanishing_poly_4x(&agg_constraint)
     }
 
     fn constraints_satisfied(&self) -> bool {
@@ -26,6 +26,40 @@ pub struct PcsOpeningAt2Points<F: PrimeField, C: Commitment<F>> {
     pub vals_at_zeta_omega: Vec<F>,
 }
 
+/// Claims that the `commitments[i]` open to `values[i]` at `points[i]`, attested by `proofs[i]`.
+pub struct OpeningClaims<F: PrimeField, CS: PCS<F>> {
+    pub commitments: Vec<CS::C>,
+    pub points: Vec<F>,
+    pub values: Vec<F>,
+    pub proofs: Vec<CS::Proof>,
+}
+
+impl<F: PrimeField, CS: PCS<F>> OpeningClaims<F, CS> {
+    pub fn empty() -> Self {
+        Self {
+            commitments: vec![],
+            points: vec![],
+            values: vec![],
+            proofs: vec![],
+        }
+    }
+
+    pub fn extend(&mut self, other: Self) {
+        self.commitments.extend(other.commitments);
+        self.points.extend(other.points);
+        self.values.extend(other.values);
+        self.proofs.extend(other.proofs);
+    }
+
+    pub fn len(&self) -> usize {
+        self.commitments.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commitments.is_empty()
+    }
+}
+
 impl<F: PrimeField, CS: PCS<F>, T: PlonkTranscript<F, CS>> PlonkVerifier<F, CS, T> {
     pub fn init(
         pcs_vk: <CS::Params as PcsParams>::VK,
@@ -83,6 +117,28 @@ impl<F: PrimeField, CS: PCS<F>, T: PlonkTranscript<F, CS>> PlonkVerifier<F, CS,
         challenges: Challenges<F>,
         rng: &mut R,
     ) -> bool
+    where
+        Piop: VerifierPiop<F, CS::C>,
+        Commitments: ColumnsCommited<F, CS::C>,
+        Evaluations: ColumnsEvaluated<F>,
+    {
+        let OpeningClaims {
+            commitments,
+            points,
+            values,
+            proofs,
+        } = self.opening_claims(piop, proof, challenges);
+        CS::batch_verify(&self.pcs_vk, commitments, points, values, proofs, rng).is_ok()
+    }
+
+    /// Reduces the Plonk proof to the PCS opening claims, that are checked by `verify`.
+    /// The claims of many proofs can be checked at once, deferring the PCS verification.
+    pub fn opening_claims<Piop, Commitments, Evaluations>(
+        &self,
+        piop: Piop,
+        proof: Proof<F, CS, Commitments, Evaluations>,
+        challenges: Challenges<F>,
+    ) -> OpeningClaims<F, CS>
     where
         Piop: VerifierPiop<F, CS::C>,
         Commitments: ColumnsCommited<F, CS::C>,
@@ -107,15 +163,12 @@ impl<F: PrimeField, CS: PCS<F>, T: PlonkTranscript<F, CS>> PlonkVerifier<F, CS,
         let lin_comm = open_at_zeta_omega[0].clone();
         let lin_at_zeta_omega = vals_at_zeta_omega[0];
 
-        CS::batch_verify(
-            &self.pcs_vk,
-            vec![agg_comm, lin_comm],
-            vec![zeta, zeta_omega],
-            vec![agg_at_zeta, lin_at_zeta_omega],
-            vec![proof.agg_at_zeta_proof, proof.lin_at_zeta_omega_proof],
-            rng,
-        )
-        .is_ok()
+        OpeningClaims {
+            commitments: vec![agg_comm, lin_comm],
+            points: vec![zeta, zeta_omega],
+            values: vec![agg_at_zeta, lin_at_zeta_omega],
+            proofs: vec![proof.agg_at_zeta_proof, proof.lin_at_zeta_omega_proof],
+        }
     }
 
     pub fn _restore_challenges<Piop, Cols, Evals>(
@@ -128,7 +181,20 @@ impl<F: PrimeField, CS: PCS<F>, T: PlonkTranscript<F, CS>> PlonkVerifier<F, CS,
         Cols: ColumnsCommited<F, CS::C>,
         Evals: ColumnsEvaluated<F>,
     {
-        let mut transcript = self.transcript_prelude.clone();
+        Self::restore_challenges_from::<Piop, _, _>(&self.transcript_prelude, instance, proof)
+    }
+
+    fn restore_challenges_from<Piop, Cols, Evals>(
+        transcript_prelude: &T,
+        instance: &Piop::Instance,
+        proof: &PiopProof<F, CS::C, Cols, Evals>,
+    ) -> (Challenges<F>, T)
+    where
+        Piop: VerifierPiop<F, CS::C>,
+        Cols: ColumnsCommited<F, CS::C>,
+        Evals: ColumnsEvaluated<F>,
+    {
+        let mut transcript = transcript_prelude.clone();
         transcript.add_instance(instance);
         transcript.add_committed_cols(&proof.column_commitments);
         // let r = transcript.get_bitmask_aggregation_challenge();
@@ -165,8 +231,26 @@ impl<F: PrimeField, CS: PCS<F>, T: PlonkTranscript<F, CS>> PlonkVerifier<F, CS,
         Cols: ColumnsCommited<F, CS::C>,
         Evals: ColumnsEvaluated<F>,
     {
-        let (challenges, mut transcript) =
-            self._restore_challenges::<Piop, _, _>(instance, &proof.to_piop_proof());
+        Self::restore_fs_with_prelude::<Piop, _, _>(&self.transcript_prelude, instance, proof)
+    }
+
+    /// Same as `restore_fs_with_rng`, but starts from the given transcript prelude,
+    /// so that verifiers sharing the PCS key don't need a `PlonkVerifier` each.
+    pub fn restore_fs_with_prelude<Piop, Cols, Evals>(
+        transcript_prelude: &T,
+        instance: &Piop::Instance,
+        proof: &Proof<F, CS, Cols, Evals>,
+    ) -> (Challenges<F>, impl RngCore)
+    where
+        Piop: VerifierPiop<F, CS::C>,
+        Cols: ColumnsCommited<F, CS::C>,
+        Evals: ColumnsEvaluated<F>,
+    {
+        let (challenges, mut transcript) = Self::restore_challenges_from::<Piop, _, _>(
+            transcript_prelude,
+            instance,
+            &proof.to_piop_proof(),
+        );
         transcript.add_kzg_proofs(&proof.agg_at_zeta_proof, &proof.lin_at_zeta_omega_proof);
         (challenges, transcript.to_rng())
     }
@@ -114,6 +114,13 @@ fn bench_prove(c: &mut Criterion) {
             let blinding_factor = Fr::rand(rng);
             b.iter(|| prover.prove(blinding_factor));
         });
+
+        let claims: Vec<(usize, Fr)> = (0..16)
+            .map(|_| (rng.gen_range(0..keyset_size), Fr::rand(rng)))
+            .collect();
+        group.bench_with_input(BenchmarkId::new("batch_16", n), &n, |b, _| {
+            b.iter(|| prover.rerandomize_pk_batch(&claims));
+        });
     }
     group.finish();
 }
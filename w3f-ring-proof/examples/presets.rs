@@ -0,0 +1,65 @@
+// Generates `src/presets/table.rs`:
+//
+// cargo run --release --example presets --features presets -- <srs-file> > src/presets/table.rs
+//
+// The SRS file should contain an uncompressed `URS<Bls12_381>` with at least `2^16` powers in G1.
+// The output is checked by `test_presets`, run with `W3F_RING_PROOF_SRS=<srs-file>`.
+
+use ark_bls12_381::G1Affine;
+use ark_ec::AffineRepr;
+use ark_ed_on_bls12_381_bandersnatch::EdwardsAffine;
+use ark_serialize::CanonicalDeserialize;
+use w3f_pcs::pcs::kzg::urs::URS;
+use w3f_ring_proof::presets::{Preset, DOMAIN_SIZES};
+
+fn main() {
+    let path = std::env::args().nth(1).expect("usage: presets <srs-file>");
+    let file = std::fs::File::open(path).unwrap();
+    let srs = URS::<ark_bls12_381::Bls12_381>::deserialize_uncompressed_unchecked(file).unwrap();
+
+    println!(
+        "// Generated with `cargo run --release --example presets --features presets -- <srs-file>`, do not edit."
+    );
+    println!();
+    println!("use ark_bls12_381::G1Affine;");
+    println!("use ark_ed_on_bls12_381_bandersnatch::EdwardsAffine;");
+    println!("use ark_ff::MontFp;");
+    println!();
+    println!("use super::{{Preset, PresetRing}};");
+    println!();
+    println!("pub(super) const PRESETS: &[Preset] = &[");
+    for domain_size in DOMAIN_SIZES {
+        let preset = Preset::compute(domain_size, &srs);
+        let ring = &preset.empty_ring;
+        println!("    Preset {{");
+        println!("        domain_size: {domain_size},");
+        println!("        h: {},", edwards(preset.h));
+        println!("        seed: {},", edwards(preset.seed));
+        println!("        empty_ring: PresetRing::empty_unchecked(");
+        println!("            {domain_size},");
+        println!("            {},", g1(ring.cx));
+        println!("            {},", g1(ring.cy));
+        println!("            {},", g1(ring.selector));
+        println!("            {},", edwards(ring.padding));
+        println!("        ),");
+        println!("        srs_digest: {:?},", preset.srs_digest);
+        println!("    }},");
+    }
+    println!("];");
+}
+
+fn edwards(p: EdwardsAffine) -> String {
+    format!(
+        "EdwardsAffine::new_unchecked(MontFp!(\"{}\"), MontFp!(\"{}\"))",
+        p.x, p.y
+    )
+}
+
+// The commitments of an empty ring are never the identity, that has no affine coordinates.
+fn g1(p: G1Affine) -> String {
+    assert!(!p.is_zero());
+    format!(
+        "G1Affine::new_unchecked(MontFp!(\"{}\"), MontFp!(\"{}\"))",
+        p.x, p.y
+    )
+}
@@ -0,0 +1,335 @@
+//! Proofs of membership in a hidden ring, e.g. in a private allow-list a service has committed to.
+//!
+//! Unlike the regular mode, where the verifier knows (or can recompute) the commitment to the ring,
+//! the commitments to the precommitted columns are blinded with a hiding IPA, and the points columns
+//! are randomized in the zk rows, so that neither the commitments nor the openings reveal the keys.
+//! The owner of the ring commits to it with fresh blinding factors for every session,
+//! so the commitments of different sessions are unlinkable. The prover should know the blinding factors,
+//! that are accounted for when opening the precommitted columns.
+//!
+//! The randomness of the zk rows is drawn once per session, and every proof opens the points columns
+//! at a fresh point, so a session is good for at most `MAX_PROOFS_PER_SESSION` proofs, see `HiddenRing`.
+
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{PrimeField, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::collections::BTreeSet;
+use ark_std::marker::PhantomData;
+use ark_std::rand::Rng;
+use ark_std::{vec, vec::Vec};
+use w3f_pcs::aggregation::multiple::ShplonkTranscript;
+use w3f_pcs::pcs::ipa::hiding::HidingIpa;
+use w3f_pcs::pcs::{PcsParams, PCS};
+use w3f_pcs::shplonk::{AggregateProof, Shplonk};
+use w3f_plonk_common::piop::{ProverPiop, VerifierPiop};
+use w3f_plonk_common::prover::{PcsOpeningAt2Points, PlonkProver};
+use w3f_plonk_common::transcript::PlonkTranscript;
+use w3f_plonk_common::verifier::{self, PlonkVerifier};
+use w3f_plonk_common::{FieldColumn, PiopProof};
+
+use crate::piop::params::{PiopParams, ZK_ROWS};
+use crate::piop::{
+    FixedColumns, FixedColumnsCommitted, PiopProver, PiopVerifier, RingCommitments,
+    RingEvaluations, VerifierKey,
+};
+use crate::ArkTranscript;
+
+type IpaCommitment<C> = <HidingIpa<C> as PCS<<C as CurveGroup>::ScalarField>>::C;
+type IpaParams<C> = <HidingIpa<C> as PCS<<C as CurveGroup>::ScalarField>>::Params;
+
+/// Blinding factors of the commitments to the precommitted columns.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct RingBlinding<F: PrimeField> {
+    pub points: [F; 2],
+    pub ring_selector: F,
+}
+
+impl<F: PrimeField> RingBlinding<F> {
+    pub fn rand<R: Rng>(rng: &mut R) -> Self {
+        Self {
+            points: [F::rand(rng), F::rand(rng)],
+            ring_selector: F::rand(rng),
+        }
+    }
+
+    // In the order of `FixedColumnsCommitted::as_vec`.
+    fn as_vec(&self) -> Vec<F> {
+        vec![self.points[0], self.points[1], self.ring_selector]
+    }
+}
+
+/// Maximal number of proofs in a session, i.e. for a single commitment to the ring.
+///
+/// A points column is randomized in `ZK_ROWS` rows, and every proof reveals its evaluation at a fresh point.
+/// Up to `ZK_ROWS` such evaluations are uniformly distributed, the further ones would leak the keys.
+pub const MAX_PROOFS_PER_SESSION: usize = ZK_ROWS;
+
+/// The prover's side of a session: the columns of the hidden ring, the blinding factors
+/// and the commitments to the columns, that the verifier gets, see `HiddenRing::commitment`.
+///
+/// Counts the proofs, so that no more than `MAX_PROOFS_PER_SESSION` are produced.
+/// For more proofs, the owner should commit to the ring anew, starting a new session.
+pub struct HiddenRing<C: CurveGroup, G: AffineRepr<BaseField = C::ScalarField>> {
+    pub fixed_columns: FixedColumns<C::ScalarField, G>,
+    pub fixed_columns_committed: FixedColumnsCommitted<C::ScalarField, IpaCommitment<C>>,
+    pub blinding: RingBlinding<C::ScalarField>,
+    proofs: usize,
+}
+
+impl<C: CurveGroup, G: AffineRepr<BaseField = C::ScalarField>> HiddenRing<C, G> {
+    pub fn commitment(&self) -> FixedColumnsCommitted<C::ScalarField, IpaCommitment<C>> {
+        self.fixed_columns_committed.clone()
+    }
+
+    pub fn proofs_left(&self) -> usize {
+        MAX_PROOFS_PER_SESSION - self.proofs
+    }
+}
+
+/// Commits to the ring of the `keys` blinding the commitments with `blinding`, starting a new session.
+/// Returns the columns together with the blinding factors, that should be passed to the prover,
+/// and the commitments to them, that the verifier gets.
+pub fn commit_hidden_ring<C: CurveGroup, G: AffineRepr<BaseField = C::ScalarField>>(
+    pcs_params: &HidingIpa<C>,
+    piop_params: &PiopParams<G>,
+    keys: &[G],
+    blinding: &RingBlinding<C::ScalarField>,
+) -> HiddenRing<C, G> {
+    let fixed_columns = piop_params.hidden_fixed_columns(keys);
+    let commit = |col: &FieldColumn<C::ScalarField>, bf| {
+        pcs_params.commit_hiding(col.as_poly(), bf).unwrap()
+    };
+    let fixed_columns_committed = FixedColumnsCommitted {
+        points: [[
+            commit(&fixed_columns.points[0].xs, blinding.points[0]),
+            commit(&fixed_columns.points[0].ys, blinding.points[1]),
+        ]],
+        ring_selector: commit(&fixed_columns.ring_selector, blinding.ring_selector),
+        phantom: PhantomData,
+    };
+    HiddenRing {
+        fixed_columns,
+        fixed_columns_committed,
+        blinding: *blinding,
+        proofs: 0,
+    }
+}
+
+/// Proof of membership in a hidden ring: the PIOP proof and the hiding opening of the columns.
+#[derive(Clone)]
+pub struct HiddenRingProof<C: CurveGroup> {
+    piop_proof: PiopProof<
+        C::ScalarField,
+        IpaCommitment<C>,
+        RingCommitments<C::ScalarField, IpaCommitment<C>>,
+        RingEvaluations<C::ScalarField>,
+    >,
+    pcs_opening_proof: AggregateProof<C::ScalarField, HidingIpa<C>>,
+}
+
+// Derives the Shplonk challenges from the PLONK transcript.
+struct ShplonkFs<T>(T);
+
+impl<F, CS, T> ShplonkTranscript<F, CS> for ShplonkFs<T>
+where
+    F: PrimeField,
+    CS: PCS<F>,
+    T: PlonkTranscript<F, CS>,
+{
+    fn get_gamma(&mut self) -> F {
+        self.0._128_bit_point(b"shplonk_gamma")
+    }
+
+    fn commit_to_q(&mut self, q: &CS::C) {
+        self.0._add_serializable(b"shplonk_q", q);
+    }
+
+    fn get_zeta(&mut self) -> F {
+        self.0._128_bit_point(b"shplonk_zeta")
+    }
+}
+
+fn verifier_key<C: CurveGroup>(
+    pcs_raw_vk: <IpaParams<C> as PcsParams>::RVK,
+    fixed_columns_committed: FixedColumnsCommitted<C::ScalarField, IpaCommitment<C>>,
+) -> VerifierKey<C::ScalarField, HidingIpa<C>> {
+    VerifierKey {
+        pcs_raw_vk,
+        fixed_columns_committed,
+    }
+}
+
+pub struct HiddenRingProver<C, G, T = ArkTranscript>
+where
+    C: CurveGroup,
+    G: AffineRepr<BaseField = C::ScalarField>,
+    T: PlonkTranscript<C::ScalarField, HidingIpa<C>>,
+{
+    pcs_params: HidingIpa<C>,
+    piop_params: PiopParams<G>,
+    empty_transcript: T,
+}
+
+impl<C, G, T> HiddenRingProver<C, G, T>
+where
+    C: CurveGroup,
+    G: AffineRepr<BaseField = C::ScalarField>,
+    T: PlonkTranscript<C::ScalarField, HidingIpa<C>>,
+    PiopProver<C::ScalarField, G>: ProverPiop<
+        C::ScalarField,
+        IpaCommitment<C>,
+        Commitments = RingCommitments<C::ScalarField, IpaCommitment<C>>,
+        Evaluations = RingEvaluations<C::ScalarField>,
+        Instance = [G; 1],
+    >,
+{
+    pub fn init(pcs_params: HidingIpa<C>, piop_params: PiopParams<G>, empty_transcript: T) -> Self {
+        Self {
+            pcs_params,
+            piop_params,
+            empty_transcript,
+        }
+    }
+
+    /// Proves membership of the `k`-th key of the hidden ring and re-randomizes it to `C' = C_k + rH`.
+    ///
+    /// Fails if the session is over, i.e. `MAX_PROOFS_PER_SESSION` proofs have been produced for the `ring`.
+    pub fn rerandomize_pk<R: Rng>(
+        &self,
+        ring: &mut HiddenRing<C, G>,
+        k: usize,
+        r: G::ScalarField,
+        rng: &mut R,
+    ) -> Result<(G, HiddenRingProof<C>), ()> {
+        if ring.proofs_left() == 0 {
+            return Err(());
+        }
+        ring.proofs += 1;
+        let piop = PiopProver::build(&self.piop_params, ring.fixed_columns.clone(), k, r);
+        let blinded_pk = <PiopProver<C::ScalarField, G> as ProverPiop<
+            C::ScalarField,
+            IpaCommitment<C>,
+        >>::result(&piop)[0];
+        let plonk_prover = PlonkProver::<C::ScalarField, HidingIpa<C>, T>::init(
+            self.pcs_params.ck(),
+            verifier_key::<C>(self.pcs_params.raw_vk(), ring.commitment()),
+            self.empty_transcript.clone(),
+        );
+        let (pcs_openings, piop_proof, mut transcript) = plonk_prover.reduce_to_pcs_opening(piop);
+        let PcsOpeningAt2Points {
+            polys_at_zeta,
+            polys_at_zeta_omega,
+            zeta,
+            zeta_omega,
+        } = pcs_openings;
+        // Keeps the transcript in sync with the verifier's.
+        let _nus = transcript.get_kzg_aggregation_challenges(polys_at_zeta.len());
+
+        let mut coord_sets = vec![BTreeSet::from([zeta]); polys_at_zeta.len()];
+        coord_sets.push(BTreeSet::from([zeta_omega]));
+        let polys = [polys_at_zeta, polys_at_zeta_omega].concat();
+        // Only the precommitted columns, that go first, are blinded.
+        let mut bfs = ring.blinding.as_vec();
+        bfs.resize(polys.len(), C::ScalarField::zero());
+        let pcs_opening_proof = Shplonk::<C::ScalarField, HidingIpa<C>>::open_many_hiding(
+            &self.pcs_params,
+            &polys,
+            &bfs,
+            &coord_sets,
+            &mut ShplonkFs(transcript),
+            rng,
+        );
+        let proof = HiddenRingProof {
+            piop_proof,
+            pcs_opening_proof,
+        };
+        Ok((blinded_pk, proof))
+    }
+}
+
+pub struct HiddenRingVerifier<C, G, T = ArkTranscript>
+where
+    C: CurveGroup,
+    G: AffineRepr<BaseField = C::ScalarField>,
+    T: PlonkTranscript<C::ScalarField, HidingIpa<C>>,
+{
+    pcs_vk: <IpaParams<C> as PcsParams>::VK,
+    pcs_raw_vk: <IpaParams<C> as PcsParams>::RVK,
+    piop_params: PiopParams<G>,
+    empty_transcript: T,
+}
+
+impl<C, G, T> HiddenRingVerifier<C, G, T>
+where
+    C: CurveGroup,
+    G: AffineRepr<BaseField = C::ScalarField>,
+    T: PlonkTranscript<C::ScalarField, HidingIpa<C>>,
+    PiopVerifier<C::ScalarField, IpaCommitment<C>, G>:
+        VerifierPiop<C::ScalarField, IpaCommitment<C>, Instance = [G; 1]>,
+{
+    pub fn init(
+        pcs_params: &HidingIpa<C>,
+        piop_params: PiopParams<G>,
+        empty_transcript: T,
+    ) -> Self {
+        Self {
+            pcs_vk: pcs_params.vk(),
+            pcs_raw_vk: pcs_params.raw_vk(),
+            piop_params,
+            empty_transcript,
+        }
+    }
+
+    /// Verifies the proof of membership in the hidden ring, given the blinded commitment to it.
+    pub fn verify(
+        &self,
+        fixed_columns_committed: FixedColumnsCommitted<C::ScalarField, IpaCommitment<C>>,
+        result: G,
+        proof: HiddenRingProof<C>,
+    ) -> bool {
+        let plonk_verifier = PlonkVerifier::<C::ScalarField, HidingIpa<C>, T>::init(
+            self.pcs_vk.clone(),
+            &verifier_key::<C>(self.pcs_raw_vk.clone(), fixed_columns_committed.clone()),
+            self.empty_transcript.clone(),
+        );
+        let HiddenRingProof {
+            piop_proof,
+            pcs_opening_proof,
+        } = proof;
+        let (challenges, transcript) = plonk_verifier
+            ._restore_challenges::<PiopVerifier<_, _, G>, _, _>(&[result], &piop_proof);
+        let seed = self.piop_params.seed;
+        let seed_plus_result = (seed + result).into_affine();
+        let domain_at_zeta = self.piop_params.domain.evaluate(challenges.zeta);
+        let piop = PiopVerifier::<_, _, G>::init(
+            domain_at_zeta,
+            fixed_columns_committed,
+            piop_proof.column_commitments.clone(),
+            piop_proof.columns_at_zeta.clone(),
+            seed.xy().unwrap(),
+            [seed_plus_result.xy().unwrap()],
+        );
+        let verifier::PcsOpeningAt2Points {
+            open_at_zeta,
+            open_at_zeta_omega,
+            zeta,
+            zeta_omega,
+            vals_at_zeta,
+            vals_at_zeta_omega,
+        } = plonk_verifier.evaluate_piop(piop, piop_proof, challenges);
+
+        let mut coord_vecs = vec![vec![zeta]; open_at_zeta.len()];
+        coord_vecs.push(vec![zeta_omega]);
+        let commitments = [open_at_zeta, open_at_zeta_omega].concat();
+        let mut vals: Vec<_> = vals_at_zeta.into_iter().map(|v| vec![v]).collect();
+        vals.push(vals_at_zeta_omega);
+        Shplonk::<C::ScalarField, HidingIpa<C>>::verify_many(
+            &self.pcs_vk,
+            &commitments,
+            pcs_opening_proof,
+            &coord_vecs,
+            &vals,
+            &mut ShplonkFs(transcript),
+        )
+    }
+}
@@ -0,0 +1,278 @@
+//! Transparent ring proofs, that require no trusted setup: ring proofs over the IPA commitment scheme.
+//!
+//! IPA doesn't need a pairing, so the outer curve can be any curve with the scalar field equal to the base field
+//! of the inner curve, e.g. a member of a cycle, such as Pallas, with the other member, Vesta, as the inner curve.
+//! Such curves have no twisted Edwards models, so the inner curve is generic here, and can be short Weierstrass.
+//!
+//! The ring is committed to with the Lagrangian basis of the IPA commitment key, see `IpaRing`,
+//! so it can be built and updated the same way as the KZG `Ring`.
+//! The proofs are produced and verified with `IpaRingProver` and `IpaRingVerifier`, i.e. with `GenericRingProver`
+//! and `GenericRingVerifier` instantiated with `IPA`.
+//!
+//! Verification of an IPA opening is linear in the domain size. Many proofs can be verified in a batch,
+//! that checks the openings of all the proofs at once, see `IpaBatchVerifier`.
+
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::marker::PhantomData;
+use ark_std::rand::RngCore;
+use ark_std::vec::Vec;
+use w3f_pcs::pcs::commitment::WrappedAffine;
+use w3f_pcs::pcs::ipa::IPA;
+use w3f_pcs::pcs::{PcsParams, PCS};
+use w3f_plonk_common::ipa_batch::IpaBatch;
+use w3f_plonk_common::piop::VerifierPiop;
+use w3f_plonk_common::transcript::PlonkTranscript;
+use w3f_plonk_common::verifier::OpeningClaims;
+
+use crate::piop::params::PiopParams;
+use crate::piop::{FixedColumnsCommitted, PiopVerifier, ProverKey, VerifierKey};
+use crate::ring::{commit_keys, commit_ring};
+use crate::ring_prover::GenericRingProver;
+use crate::ring_verifier::GenericRingVerifier;
+use crate::{ArkTranscript, RingProof};
+
+pub type IpaCommitment<C> = <IPA<C> as PCS<<C as CurveGroup>::ScalarField>>::C;
+type IpaParams<C> = <IPA<C> as PCS<<C as CurveGroup>::ScalarField>>::Params;
+type IpaCk<C> = <IPA<C> as PCS<<C as CurveGroup>::ScalarField>>::CK;
+type IpaVk<C> = <IPA<C> as PCS<<C as CurveGroup>::ScalarField>>::VK;
+type IpaRawVk<C> = <IpaParams<C> as PcsParams>::RVK;
+
+pub type IpaRingProof<C> = RingProof<<C as CurveGroup>::ScalarField, IPA<C>>;
+
+/// Prover of membership in the ring of the inner curve points `G`, committed with the IPA over the curve `C`.
+pub type IpaRingProver<C, G, T = ArkTranscript> =
+    GenericRingProver<<C as CurveGroup>::ScalarField, IPA<C>, G, T>;
+
+/// Verifier of membership in the ring, see `IpaRingProver`.
+pub type IpaRingVerifier<C, G, T = ArkTranscript> =
+    GenericRingVerifier<<C as CurveGroup>::ScalarField, IPA<C>, G, T>;
+
+/// Lagrangian basis of the IPA commitment key, used to build the `IpaRing`, see `RingBuilderKey`.
+#[derive(Clone)]
+pub struct IpaRingBuilderKey<C: CurveGroup> {
+    // Lagrangian basis
+    pub lis: Vec<C::Affine>,
+    // commitment to the constant `1` polynomial
+    pub g: C,
+}
+
+impl<C: CurveGroup> IpaRingBuilderKey<C> {
+    /// Computes the Lagrangian basis as the inverse FFT of the monomial basis `G0, ..., G(n-1)` of the key,
+    /// that takes `O(n.log(n))` group operations.
+    pub fn from_ck(ck: &IpaCk<C>, domain_size: usize) -> Self {
+        let domain = GeneralEvaluationDomain::<C::ScalarField>::new(domain_size).unwrap();
+        assert_eq!(domain.size(), domain_size);
+        assert!(ck.g.len() >= domain_size);
+        let monomial_basis: Vec<C> = ck.g[..domain_size].iter().map(|g| g.into_group()).collect();
+        // `Li = 1/n sum[w^(-ij).Gj, j = 0,...,n-1]`, as `Li(X) = 1/n sum[w^(-ij).X^j, j = 0,...,n-1]`.
+        let lis = C::normalize_batch(&domain.ifft(&monomial_basis));
+        let g = monomial_basis[0];
+        Self { lis, g }
+    }
+}
+
+/// Commitment to a list of keys, built with the IPA commitment key in the Lagrangian form.
+/// Commits to the same vector of points as the KZG `Ring` does, see `Ring` for the details.
+#[derive(Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct IpaRing<C: CurveGroup, G: AffineRepr<BaseField = C::ScalarField>> {
+    /// IPA commitment to the x coordinates of the described vector.
+    pub cx: C::Affine,
+    /// IPA commitment to the y coordinates of the described vector.
+    pub cy: C::Affine,
+    /// IPA commitment to a bitvector highlighting the part of the vector corresponding to the public keys.
+    pub selector: C::Affine,
+    /// Maximal number of keys the commitment can "store".
+    pub max_keys: usize,
+    /// Number of keys "stored" in this commitment.
+    pub curr_keys: usize,
+    // Padding point.
+    pub padding: G,
+}
+
+impl<C: CurveGroup, G: AffineRepr<BaseField = C::ScalarField>> IpaRing<C, G> {
+    /// Builds the ring from the keys provided with 2 MSMs of size `keys.len() + scalar_bitlen + 5`.
+    pub fn with_keys(
+        piop_params: &PiopParams<G>,
+        keys: &[G],
+        builder_key: &IpaRingBuilderKey<C>,
+    ) -> Self {
+        let [cx, cy, selector] = commit_ring(piop_params, keys, &builder_key.lis, builder_key.g);
+        Self {
+            cx,
+            cy,
+            selector,
+            max_keys: piop_params.keyset_part_size,
+            curr_keys: keys.len(),
+            padding: piop_params.padding,
+        }
+    }
+
+    /// Appends a set key sequence to the ring with 2 MSMs of size `keys.len()`.
+    pub fn append(&mut self, keys: &[G], builder_key: &IpaRingBuilderKey<C>) {
+        let new_size = self.curr_keys + keys.len();
+        assert!(new_size <= self.max_keys);
+        let bases = &builder_key.lis[self.curr_keys..new_size];
+        let (cx_delta, cy_delta) = commit_keys::<C, G>(keys, self.padding, bases);
+        let affine = C::normalize_batch(&[self.cx + cx_delta, self.cy + cy_delta]);
+        self.cx = affine[0];
+        self.cy = affine[1];
+        self.curr_keys = new_size;
+    }
+
+    pub fn slots_left(&self) -> usize {
+        self.max_keys - self.curr_keys
+    }
+}
+
+impl<C: CurveGroup> FixedColumnsCommitted<C::ScalarField, WrappedAffine<C>> {
+    pub fn from_ipa_ring<G: AffineRepr<BaseField = C::ScalarField>>(ring: &IpaRing<C, G>) -> Self {
+        Self {
+            points: [[WrappedAffine(ring.cx), WrappedAffine(ring.cy)]],
+            ring_selector: WrappedAffine(ring.selector),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<C: CurveGroup> VerifierKey<C::ScalarField, IPA<C>> {
+    pub fn from_ipa_ring<G: AffineRepr<BaseField = C::ScalarField>>(
+        ring: &IpaRing<C, G>,
+        pcs_raw_vk: IpaRawVk<C>,
+    ) -> Self {
+        Self {
+            pcs_raw_vk,
+            fixed_columns_committed: FixedColumnsCommitted::from_ipa_ring(ring),
+        }
+    }
+}
+
+impl<C: CurveGroup, G: AffineRepr<BaseField = C::ScalarField>>
+    ProverKey<C::ScalarField, IPA<C>, G>
+{
+    /// Builds the prover key for a `ring` holding the `keys`, taking the commitments to the fixed columns from the ring.
+    /// See `ProverKey::from_ring`.
+    pub fn from_ipa_ring(
+        ring: &IpaRing<C, G>,
+        keys: &[G],
+        piop_params: &PiopParams<G>,
+        pcs_params: &IpaParams<C>,
+    ) -> Self {
+        assert_eq!(keys.len(), ring.curr_keys);
+        let pcs_ck = pcs_params.ck();
+        let fixed_columns = piop_params.fixed_columns(keys);
+        let verifier_key = VerifierKey::from_ipa_ring(ring, pcs_params.raw_vk());
+        debug_assert_eq!(
+            fixed_columns.commit::<IPA<C>>(&pcs_ck),
+            verifier_key.fixed_columns_committed,
+            "ring doesn't match the keys"
+        );
+        Self {
+            pcs_ck,
+            fixed_columns,
+            verifier_key,
+        }
+    }
+}
+
+impl<C, G, T> IpaRingVerifier<C, G, T>
+where
+    C: CurveGroup,
+    G: AffineRepr<BaseField = C::ScalarField>,
+    T: PlonkTranscript<C::ScalarField, IPA<C>>,
+    PiopVerifier<C::ScalarField, IpaCommitment<C>, G>:
+        VerifierPiop<C::ScalarField, IpaCommitment<C>, Instance = [G; 1]>,
+{
+    /// Verifies the proofs in a batch, see `IpaBatchVerifier`.
+    /// Proofs are paired with the results positionally. Fails if the two vectors differ in length.
+    pub fn verify_batch_ipa(&self, proofs: Vec<IpaRingProof<C>>, results: Vec<G>) -> bool {
+        if proofs.len() != results.len() {
+            return false;
+        }
+        let mut batch = IpaBatchVerifier::new(
+            self.plonk_verifier.pcs_vk.clone(),
+            self.plonk_verifier.transcript_prelude.clone(),
+        );
+        for (proof, result) in proofs.into_iter().zip(results) {
+            batch.push(self, proof, result);
+        }
+        batch.verify()
+    }
+
+    // Reduces the proof to the IPA opening claims.
+    // Also returns the rng derived from the proof transcript.
+    pub(crate) fn opening_claims(
+        &self,
+        proof: IpaRingProof<C>,
+        result: G,
+    ) -> (OpeningClaims<C::ScalarField, IPA<C>>, impl RngCore) {
+        let (challenges, fs_rng) = self
+            .plonk_verifier
+            .restore_fs_with_rng::<PiopVerifier<_, _, G>, _, _>(&[result], &proof);
+        let seed = self.piop_params.seed;
+        let seed_plus_result = (seed + result).into_affine();
+        let domain_at_zeta = self.piop_params.domain.evaluate(challenges.zeta);
+        let piop = PiopVerifier::<_, _, G>::init(
+            domain_at_zeta,
+            self.fixed_columns_committed.clone(),
+            proof.column_commitments.clone(),
+            proof.columns_at_zeta.clone(),
+            seed.xy().unwrap(),
+            [seed_plus_result.xy().unwrap()],
+        );
+        let claims = self.plonk_verifier.opening_claims(piop, proof, challenges);
+        (claims, fs_rng)
+    }
+}
+
+/// Batch verifier for IPA ring proofs across one or more rings, that share the IPA commitment key.
+///
+/// Reduces every proof to the IPA opening claims, deferring the linear-time PCS checks
+/// to the end, when the claims of all the proofs are checked at once, see `IpaBatch`.
+pub struct IpaBatchVerifier<C, T>
+where
+    C: CurveGroup,
+    T: PlonkTranscript<C::ScalarField, IPA<C>>,
+{
+    batch: IpaBatch<C>,
+    // Absorbs the entropy derived from every proof pushed.
+    transcript: T,
+}
+
+impl<C, T> IpaBatchVerifier<C, T>
+where
+    C: CurveGroup,
+    T: PlonkTranscript<C::ScalarField, IPA<C>>,
+{
+    pub fn new(pcs_vk: IpaVk<C>, transcript: T) -> Self {
+        Self {
+            batch: IpaBatch::new(pcs_vk),
+            transcript,
+        }
+    }
+
+    /// Adds a ring proof to the batch.
+    pub fn push<G>(
+        &mut self,
+        verifier: &IpaRingVerifier<C, G, T>,
+        proof: IpaRingProof<C>,
+        result: G,
+    ) where
+        G: AffineRepr<BaseField = C::ScalarField>,
+        PiopVerifier<C::ScalarField, IpaCommitment<C>, G>:
+            VerifierPiop<C::ScalarField, IpaCommitment<C>, Instance = [G; 1]>,
+    {
+        let (claims, mut fs_rng) = verifier.opening_claims(proof, result);
+        let mut entropy = [0_u8; 32];
+        fs_rng.fill_bytes(&mut entropy);
+        self.transcript
+            ._add_serializable(b"batch-entropy", &entropy);
+        self.batch.push_claims(claims);
+    }
+
+    /// Verifies all the proofs in the batch at once.
+    pub fn verify(self) -> bool {
+        self.batch.verify(&mut self.transcript.to_rng())
+    }
+}
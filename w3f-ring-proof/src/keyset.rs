@@ -0,0 +1,174 @@
+use ark_ec::AffineRepr;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::marker::PhantomData;
+use ark_std::vec::Vec;
+
+/// Keyset in the canonical order: sorted by the compressed encodings of the keys, without duplicates.
+///
+/// `Ring::with_keys` and `PiopParams::fixed_columns` commit to the keys in the order given,
+/// so the parties that collected the same set of keys in different orders would build different rings.
+/// Building the rings from `CanonicalKeyset::keys` instead makes them match.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct CanonicalKeyset<G: AffineRepr> {
+    keys: Vec<G>,
+}
+
+impl<G: AffineRepr> CanonicalKeyset<G> {
+    /// Sorts and deduplicates the keys.
+    pub fn new(keys: &[G]) -> Self {
+        let mut keys = keys.to_vec();
+        keys.sort_by_cached_key(encode);
+        keys.dedup();
+        Self { keys }
+    }
+
+    /// Same as `new`, but also returns the position map: the `i`-th element is the index of `keys[i]` in the keyset,
+    /// that is the index `RingProver::init` expects.
+    pub fn with_positions(keys: &[G]) -> (Self, Vec<usize>) {
+        let keyset = Self::new(keys);
+        let positions = keys
+            .iter()
+            .map(|key| keyset.position(key).unwrap())
+            .collect();
+        (keyset, positions)
+    }
+
+    /// Index of the key in the keyset, if it's there.
+    pub fn position(&self, key: &G) -> Option<usize> {
+        let encoding = encode(key);
+        self.keys.binary_search_by_key(&encoding, encode).ok()
+    }
+
+    /// The keys in the canonical order.
+    pub fn keys(&self) -> &[G] {
+        &self.keys
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
+
+/// Map from the keys of a ring, in any order, to their slots.
+/// Saves a linear scan over the ring for every lookup, see `RingProver::for_key_with_index`.
+pub struct KeyIndex<G: AffineRepr> {
+    // The encodings of the keys with their slots, sorted by the encodings.
+    slots: Vec<(Vec<u8>, usize)>,
+    _phantom: PhantomData<G>,
+}
+
+impl<G: AffineRepr> KeyIndex<G> {
+    pub fn new(keys: &[G]) -> Self {
+        let mut slots: Vec<_> = keys.iter().map(encode).zip(0..).collect();
+        slots.sort();
+        Self {
+            slots,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Slot of the key. Fails if the key is absent, or occupies more than one slot.
+    pub fn position(&self, key: &G) -> Result<usize, ()> {
+        let encoding = encode(key);
+        let from = self.slots.partition_point(|(e, _)| *e < encoding);
+        match &self.slots[from..] {
+            [(e, slot), rest @ ..] if *e == encoding => match rest.first() {
+                Some((next, _)) if *next == encoding => Err(()),
+                _ => Ok(*slot),
+            },
+            _ => Err(()),
+        }
+    }
+}
+
+fn encode<G: AffineRepr>(key: &G) -> Vec<u8> {
+    let mut encoding = Vec::with_capacity(key.compressed_size());
+    key.serialize_compressed(&mut encoding).unwrap();
+    encoding
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::Bls12_381;
+    use ark_ed_on_bls12_381_bandersnatch::EdwardsAffine;
+    use ark_std::ops::Range;
+    use ark_std::rand::seq::SliceRandom;
+    use ark_std::{test_rng, UniformRand};
+    use w3f_pcs::pcs::kzg::KZG;
+    use w3f_pcs::pcs::PCS;
+
+    use w3f_plonk_common::test_helpers::random_vec;
+    use w3f_plonk_common::Column;
+
+    use crate::ring::{Ring, RingBuilderKey};
+    use crate::PiopParams;
+
+    use super::*;
+
+    #[test]
+    fn test_canonical_keyset() {
+        let rng = &mut test_rng();
+
+        let domain_size = 1 << 9;
+
+        let pcs_params = KZG::<Bls12_381>::setup(domain_size - 1, rng);
+        let ring_builder_key = RingBuilderKey::from_srs(&pcs_params, domain_size);
+        let piop_params = PiopParams::rand(domain_size, rng);
+
+        let keys = random_vec::<EdwardsAffine, _>(10, rng);
+        let mut other_keys = [keys.clone(), keys[..3].to_vec()].concat();
+        other_keys.shuffle(rng);
+
+        let (keyset, positions) = CanonicalKeyset::with_positions(&keys);
+        let (other_keyset, other_positions) = CanonicalKeyset::with_positions(&other_keys);
+        assert_eq!(keyset, other_keyset);
+        assert_eq!(keyset.len(), 10);
+        for (key, i) in other_keys.iter().zip(other_positions) {
+            assert_eq!(keyset.keys()[i], *key);
+        }
+        for (key, i) in keys.iter().zip(positions) {
+            assert_eq!(keyset.position(key), Some(i));
+        }
+        assert_eq!(keyset.position(&piop_params.padding), None);
+
+        let ring =
+            Ring::<_, Bls12_381, _>::with_keys(&piop_params, keyset.keys(), &ring_builder_key);
+        let other_ring = Ring::with_keys(&piop_params, other_keyset.keys(), &ring_builder_key);
+        assert_eq!(ring, other_ring);
+        assert_ne!(
+            ring,
+            Ring::with_keys(&piop_params, &keys, &ring_builder_key)
+        );
+
+        let srs = |range: Range<usize>| Ok(ring_builder_key.lis_in_g1[range].to_vec());
+        let mut appended_ring = Ring::empty(&piop_params, srs, ring_builder_key.g1);
+        appended_ring.append(other_keyset.keys(), srs);
+        assert_eq!(ring, appended_ring);
+        let fixed_columns = piop_params.fixed_columns(other_keyset.keys());
+        assert_eq!(
+            fixed_columns.points[0].payload()[..keyset.len()],
+            *keyset.keys()
+        );
+    }
+
+    #[test]
+    fn test_key_index() {
+        let rng = &mut test_rng();
+
+        let mut keys = random_vec::<EdwardsAffine, _>(10, rng);
+        let index = KeyIndex::new(&keys);
+        for (i, key) in keys.iter().enumerate() {
+            assert_eq!(index.position(key), Ok(i));
+        }
+        assert!(index.position(&EdwardsAffine::rand(rng)).is_err());
+
+        keys[5] = keys[2];
+        let index = KeyIndex::new(&keys);
+        assert!(index.position(&keys[2]).is_err());
+        assert_eq!(index.position(&keys[3]), Ok(3));
+    }
+}
@@ -4,21 +4,57 @@ use ark_serialize::CanonicalSerialize;
 use ark_std::rand::RngCore;
 use w3f_pcs::pcs::PCS;
 
-pub use piop::index;
+pub use piop::{index, index_tuples, index_weighted};
 pub use w3f_plonk_common::domain::Domain;
 use w3f_plonk_common::Proof;
 
-pub use crate::piop::{params::PiopParams, FixedColumnsCommitted, ProverKey, VerifierKey};
+use crate::piop::many::{ManyRingCommitments, ManyRingEvaluations};
+use crate::piop::subset::SubsetRingEvaluations;
+pub use crate::piop::subset::SubsetSelector;
+pub use crate::piop::weighted::{
+    RingWeights, WeightedProverKey, WeightedVerifierKey, WeightsCommitted,
+};
+use crate::piop::weighted::{WeightedRingCommitments, WeightedRingEvaluations};
+pub use crate::piop::{
+    params::{PiopParams, TupleParams},
+    CompactProverKey, FixedColumnsCommitted, PackedProverKey, ProverKey, VerifierKey,
+};
 use crate::piop::{RingCommitments, RingEvaluations};
 
+pub mod hidden;
+pub mod ipa;
+pub mod keyset;
 pub mod multi_ring_batch_verifier;
 pub mod piop;
+#[cfg(feature = "presets")]
+pub mod presets;
+pub mod registry;
 pub mod ring;
 pub mod ring_prover;
 pub mod ring_verifier;
+pub mod tracing;
 
 pub type RingProof<F, CS> = Proof<F, CS, RingCommitments<F, <CS as PCS<F>>::C>, RingEvaluations<F>>;
 
+/// Proof of membership in a subset of the ring, see `RingProver::rerandomize_pk_in_subset`.
+pub type SubsetRingProof<F, CS> =
+    Proof<F, CS, RingCommitments<F, <CS as PCS<F>>::C>, SubsetRingEvaluations<F>>;
+
+/// Proof of membership of `K` distinct keys in the ring, see `RingProver::rerandomize_many`.
+pub type ManyRingProof<F, CS, const K: usize> =
+    Proof<F, CS, ManyRingCommitments<F, <CS as PCS<F>>::C, K>, ManyRingEvaluations<F, K>>;
+
+/// Proof of membership of a key tuple in a ring of `K`-tuples, see `TupleRingProver::rerandomize_pks`.
+pub type TupleRingProof<F, CS, const K: usize> =
+    Proof<F, CS, RingCommitments<F, <CS as PCS<F>>::C, K>, RingEvaluations<F, K>>;
+
+/// Proof of membership in a traceable ring, see the `tracing` module.
+pub type TraceableRingProof<F, CS> = TupleRingProof<F, CS, 2>;
+
+/// Proof that the weight of a ring member is at least a threshold, see `WeightedRingProver::rerandomize_pk`.
+pub type WeightedRingProof<F, CS> =
+    Proof<F, CS, WeightedRingCommitments<F, <CS as PCS<F>>::C>, WeightedRingEvaluations<F>>;
+
 /// Polynomial Commitment Schemes.
 pub use w3f_pcs::pcs;
 
@@ -53,16 +89,20 @@ mod tests {
     use ark_bls12_381::Bls12_381;
     use ark_ec::CurveGroup;
     use ark_ed_on_bls12_381_bandersnatch::{BandersnatchConfig, EdwardsAffine, Fq, Fr};
+    use ark_serialize::CanonicalDeserialize;
     use ark_std::ops::Mul;
     use ark_std::rand::Rng;
     use ark_std::{end_timer, start_timer, test_rng, UniformRand};
+    use w3f_pcs::pcs::commitment::WrappedAffine;
     use w3f_pcs::pcs::kzg::KZG;
+    use w3f_pcs::pcs::PcsParams;
 
     use w3f_plonk_common::test_helpers::random_vec;
+    use w3f_plonk_common::Column;
 
-    use crate::ring::{Ring, RingBuilderKey};
-    use crate::ring_prover::RingProver;
-    use crate::ring_verifier::RingVerifier;
+    use crate::ring::{Ring, RingBuilderKey, TupleRing};
+    use crate::ring_prover::{RingProver, TupleRingProver, WeightedRingProver};
+    use crate::ring_verifier::{RingVerifier, TupleRingVerifier, WeightedRingVerifier};
 
     use super::*;
 
@@ -139,6 +179,207 @@ mod tests {
         assert!(!verifier.verify_batch_kzg(vec![proof], Vec::new()));
     }
 
+    #[test]
+    fn test_prover_for_key() {
+        use crate::keyset::KeyIndex;
+
+        let rng = &mut test_rng();
+
+        let (pcs_params, piop_params) = setup::<_, KZG<Bls12_381>>(rng, 2usize.pow(9));
+        let mut pks = random_vec::<EdwardsAffine, _>(10, rng);
+        let (prover_key, verifier_key) =
+            index::<_, KZG<Bls12_381>, _>(&pcs_params, &piop_params, &pks);
+        let verifier = RingVerifier::init(
+            verifier_key,
+            piop_params.clone(),
+            ArkTranscript::new(b"w3f-ring-proof-test"),
+        );
+        let for_key = |prover_key: &ProverKey<_, _, _>, pk| {
+            RingProver::for_key(
+                prover_key.clone(),
+                piop_params.clone(),
+                pk,
+                ArkTranscript::new(b"w3f-ring-proof-test"),
+            )
+        };
+
+        let prover = for_key(&prover_key, pks[7]).unwrap();
+        let t = Fr::rand(rng);
+        let proof = prover.prove(t);
+        assert!(verifier.verify(proof, piop_params.blind_pk(pks[7], t)));
+
+        let key_index = KeyIndex::new(&pks);
+        let prover = RingProver::for_key_with_index(
+            prover_key.clone(),
+            piop_params.clone(),
+            pks[7],
+            &key_index,
+            ArkTranscript::new(b"w3f-ring-proof-test"),
+        )
+        .unwrap();
+        let proof = prover.prove(t);
+        assert!(verifier.verify(proof, piop_params.blind_pk(pks[7], t)));
+
+        assert!(for_key(&prover_key, EdwardsAffine::rand(rng)).is_err());
+        assert!(for_key(&prover_key, piop_params.padding).is_err());
+
+        pks[3] = pks[7];
+        let (prover_key, _) = index::<_, KZG<Bls12_381>, _>(&pcs_params, &piop_params, &pks);
+        assert!(for_key(&prover_key, pks[7]).is_err());
+        assert!(for_key(&prover_key, pks[2]).is_ok());
+    }
+
+    #[test]
+    fn test_rerandomize_pk_batch() {
+        let rng = &mut test_rng();
+
+        let (pcs_params, mut piop_params) = setup::<_, KZG<Bls12_381>>(rng, 2usize.pow(9));
+        // Deterministic proofs, so that the batch can be compared against the sequential proving.
+        piop_params.domain = piop_params.domain.without_blinding();
+        let keyset_size = piop_params.keyset_part_size;
+        let pks = random_vec::<EdwardsAffine, _>(keyset_size, rng);
+        let (prover_key, verifier_key) =
+            index::<_, KZG<Bls12_381>, _>(&pcs_params, &piop_params, &pks);
+
+        let prover = RingProver::init(
+            prover_key,
+            piop_params.clone(),
+            0,
+            ArkTranscript::new(b"w3f-ring-proof-test"),
+        );
+        let claims: Vec<(usize, Fr)> = (0..4)
+            .map(|_| (rng.gen_range(0..keyset_size), Fr::rand(rng)))
+            .collect();
+        let batch = prover.rerandomize_pk_batch(&claims);
+        assert_eq!(batch.len(), claims.len());
+
+        for (&(k, r), (blinded_pk, proof)) in claims.iter().zip(&batch) {
+            let (expected_pk, expected_proof) = prover.rerandomize_pk(k, r);
+            assert_eq!(*blinded_pk, expected_pk);
+            let mut proof_bytes = Vec::new();
+            proof.serialize_compressed(&mut proof_bytes).unwrap();
+            let mut expected_proof_bytes = Vec::new();
+            expected_proof
+                .serialize_compressed(&mut expected_proof_bytes)
+                .unwrap();
+            assert_eq!(proof_bytes, expected_proof_bytes);
+        }
+
+        let ring_verifier = RingVerifier::init(
+            verifier_key,
+            piop_params,
+            ArkTranscript::new(b"w3f-ring-proof-test"),
+        );
+        let (blinded_pks, proofs) = batch.into_iter().unzip();
+        assert!(ring_verifier.verify_batch_kzg(proofs, blinded_pks));
+    }
+
+    #[test]
+    fn test_compact_prover_key() {
+        let rng = &mut test_rng();
+
+        let (pcs_params, mut piop_params) = setup::<_, KZG<Bls12_381>>(rng, 2usize.pow(9));
+        piop_params.domain = piop_params.domain.without_blinding();
+        let keyset_size = piop_params.keyset_part_size;
+        let pks = random_vec::<EdwardsAffine, _>(keyset_size, rng);
+        let (prover_key, verifier_key) =
+            index::<_, KZG<Bls12_381>, _>(&pcs_params, &piop_params, &pks);
+
+        let compact_prover_key = prover_key.compact();
+        let mut compact_bytes = Vec::new();
+        compact_prover_key
+            .serialize_compressed(&mut compact_bytes)
+            .unwrap();
+        let mut full_bytes = Vec::new();
+        prover_key.serialize_compressed(&mut full_bytes).unwrap();
+        assert!(compact_bytes.len() < full_bytes.len());
+        let compact_prover_key =
+            CompactProverKey::<_, KZG<Bls12_381>, EdwardsAffine>::deserialize_compressed(
+                &compact_bytes[..],
+            )
+            .unwrap();
+        let mut tampered = compact_prover_key.clone();
+        tampered.fixed_columns.ring_selector = tampered.fixed_columns.points.xs.clone();
+        let mut tampered_bytes = Vec::new();
+        tampered.serialize_compressed(&mut tampered_bytes).unwrap();
+        assert!(
+            CompactProverKey::<_, KZG<Bls12_381>, EdwardsAffine>::deserialize_compressed(
+                &tampered_bytes[..]
+            )
+            .is_err()
+        );
+
+        let k = rng.gen_range(0..keyset_size);
+        let r = Fr::rand(rng);
+        let prover = RingProver::init(
+            prover_key,
+            piop_params.clone(),
+            k,
+            ArkTranscript::new(b"w3f-ring-proof-test"),
+        );
+        let compact_prover = RingProver::init_compact(
+            compact_prover_key,
+            piop_params.clone(),
+            k,
+            ArkTranscript::new(b"w3f-ring-proof-test"),
+        );
+        let mut proof_bytes = Vec::new();
+        prover
+            .prove(r)
+            .serialize_compressed(&mut proof_bytes)
+            .unwrap();
+        let proof = compact_prover.prove(r);
+        let mut compact_proof_bytes = Vec::new();
+        proof
+            .serialize_compressed(&mut compact_proof_bytes)
+            .unwrap();
+        assert_eq!(proof_bytes, compact_proof_bytes);
+
+        let ring_verifier = RingVerifier::init(
+            verifier_key,
+            piop_params.clone(),
+            ArkTranscript::new(b"w3f-ring-proof-test"),
+        );
+        assert!(ring_verifier.verify(proof, piop_params.blind_pk(pks[k], r)));
+    }
+
+    #[test]
+    fn test_packed_prover_key() {
+        let rng = &mut test_rng();
+
+        let (pcs_params, piop_params) = setup::<_, KZG<Bls12_381>>(rng, 2usize.pow(9));
+        let keyset_size = piop_params.keyset_part_size / 2;
+        let pks = random_vec::<EdwardsAffine, _>(keyset_size, rng);
+        let (prover_key, _) = index::<_, KZG<Bls12_381>, _>(&pcs_params, &piop_params, &pks);
+
+        let packed = prover_key.pack(&piop_params);
+        assert_eq!(packed.keys, pks);
+        let mut packed_bytes = Vec::new();
+        packed.serialize_compressed(&mut packed_bytes).unwrap();
+        let mut full_bytes = Vec::new();
+        prover_key.serialize_compressed(&mut full_bytes).unwrap();
+        assert!(packed_bytes.len() < full_bytes.len());
+
+        let packed = PackedProverKey::<Fq, KZG<Bls12_381>, EdwardsAffine>::deserialize_compressed(
+            &packed_bytes[..],
+        )
+        .unwrap();
+        let unpacked = packed.unpack(&piop_params).unwrap();
+        let mut unpacked_bytes = Vec::new();
+        unpacked.serialize_compressed(&mut unpacked_bytes).unwrap();
+        assert_eq!(unpacked_bytes, full_bytes);
+
+        // The key packed under the other parameters is rejected, even if the digest is forged.
+        let other_piop_params = PiopParams::rand(2usize.pow(9), rng);
+        assert_ne!(piop_params.digest(), other_piop_params.digest());
+        let packed = prover_key.pack(&piop_params);
+        assert_eq!(packed.piop_params_digest, piop_params.digest());
+        assert!(packed.unpack(&other_piop_params).is_err());
+        let mut packed = prover_key.pack(&piop_params);
+        packed.piop_params_digest = other_piop_params.digest();
+        assert!(packed.unpack(&other_piop_params).is_err());
+    }
+
     #[test]
     fn test_lagrangian_commitment() {
         let rng = &mut test_rng();
@@ -163,6 +404,555 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_prover_key_from_ring() {
+        let rng = &mut test_rng();
+
+        let domain_size = 2usize.pow(9);
+        let (pcs_params, piop_params) = setup::<_, KZG<Bls12_381>>(rng, domain_size);
+        let ring_builder_key = RingBuilderKey::from_srs(&pcs_params, domain_size);
+
+        let keyset_size = rng.gen_range(0..piop_params.keyset_part_size);
+        let pks = random_vec::<EdwardsAffine, _>(keyset_size, rng);
+        let ring = Ring::<_, Bls12_381, _>::with_keys(&piop_params, &pks, &ring_builder_key);
+
+        let prover_key = ProverKey::from_ring(&ring, &pks, &piop_params, &pcs_params);
+        let (expected_prover_key, _) =
+            index::<_, KZG<Bls12_381>, _>(&pcs_params, &piop_params, &pks);
+        let mut prover_key_bytes = Vec::new();
+        prover_key
+            .serialize_compressed(&mut prover_key_bytes)
+            .unwrap();
+        let mut expected_prover_key_bytes = Vec::new();
+        expected_prover_key
+            .serialize_compressed(&mut expected_prover_key_bytes)
+            .unwrap();
+        assert_eq!(prover_key_bytes, expected_prover_key_bytes);
+    }
+
+    #[test]
+    fn test_prover_key_updates() {
+        let rng = &mut test_rng();
+
+        let domain_size = 2usize.pow(9);
+        let (pcs_params, piop_params) = setup::<_, KZG<Bls12_381>>(rng, domain_size);
+        let ring_builder_key = RingBuilderKey::from_srs(&pcs_params, domain_size);
+        let srs =
+            |range: ark_std::ops::Range<usize>| Ok(ring_builder_key.lis_in_g1[range].to_vec());
+
+        let mut pks = random_vec::<EdwardsAffine, _>(10, rng);
+        let (mut prover_key, _) = index::<_, KZG<Bls12_381>, _>(&pcs_params, &piop_params, &pks);
+        let mut ring = Ring::<_, Bls12_381, _>::with_keys(&piop_params, &pks, &ring_builder_key);
+
+        let new_pks = random_vec::<EdwardsAffine, _>(5, rng);
+        prover_key.append_keys(&new_pks, &piop_params, srs);
+        ring.append(&new_pks, srs);
+        pks.extend(new_pks);
+
+        let new_pk = EdwardsAffine::rand(rng);
+        prover_key.replace_key(3, new_pk, &piop_params, srs);
+        ring.replace_key(3, pks[3], new_pk, srs);
+        pks[3] = new_pk;
+
+        let (expected_prover_key, expected_verifier_key) =
+            index::<_, KZG<Bls12_381>, _>(&pcs_params, &piop_params, &pks);
+        assert_eq!(prover_key.verifier_key, expected_verifier_key);
+        assert_eq!(
+            prover_key.verifier_key.fixed_columns_committed,
+            FixedColumnsCommitted::from_ring(&ring)
+        );
+        let mut prover_key_bytes = Vec::new();
+        prover_key
+            .serialize_compressed(&mut prover_key_bytes)
+            .unwrap();
+        let mut expected_prover_key_bytes = Vec::new();
+        expected_prover_key
+            .serialize_compressed(&mut expected_prover_key_bytes)
+            .unwrap();
+        assert_eq!(prover_key_bytes, expected_prover_key_bytes);
+    }
+
+    #[test]
+    fn test_subset_ring_proof() {
+        let rng = &mut test_rng();
+
+        let domain_size = 2usize.pow(9);
+        let (pcs_params, piop_params) = setup::<_, KZG<Bls12_381>>(rng, domain_size);
+        let ring_builder_key = RingBuilderKey::from_srs(&pcs_params, domain_size);
+        let srs =
+            |range: ark_std::ops::Range<usize>| Ok(ring_builder_key.lis_in_g1[range].to_vec());
+
+        let pks = random_vec::<EdwardsAffine, _>(100, rng);
+        let (prover_key, verifier_key) =
+            index::<_, KZG<Bls12_381>, _>(&pcs_params, &piop_params, &pks);
+        let ring = Ring::<_, Bls12_381, _>::with_keys(&piop_params, &pks, &ring_builder_key);
+
+        let slots = 20..40;
+        let column = piop_params.subset_selector(&slots.clone().collect::<Vec<_>>());
+        let commitment = KZG::<Bls12_381>::commit(&prover_key.pcs_ck, column.as_poly()).unwrap();
+        // The commitment to the selector can be computed from the Lagrangian SRS alone.
+        assert_eq!(commitment.0, ring.range_selector(slots.clone(), srs));
+        let subset_selector = SubsetSelector { column, commitment };
+
+        let prover = RingProver::init(
+            prover_key,
+            piop_params.clone(),
+            0,
+            ArkTranscript::new(b"w3f-ring-proof-test"),
+        );
+        let verifier = RingVerifier::init(
+            verifier_key,
+            piop_params,
+            ArkTranscript::new(b"w3f-ring-proof-test"),
+        );
+
+        let (result, proof) = prover.rerandomize_pk_in_subset(&subset_selector, 25, Fr::rand(rng));
+        assert!(verifier.verify_in_subset(
+            proof.clone(),
+            result,
+            subset_selector.commitment.clone()
+        ));
+        // The proof is bound to the subset.
+        let other_subset = ring.subset_selector(&[25, 50], srs);
+        assert!(!verifier.verify_in_subset(proof, result, WrappedAffine(other_subset)));
+
+        // The ring member is outside of the subset.
+        let (result, proof) = prover.rerandomize_pk_in_subset(&subset_selector, 50, Fr::rand(rng));
+        assert!(!verifier.verify_in_subset(proof, result, subset_selector.commitment));
+    }
+
+    #[test]
+    fn test_heterogeneous_batch_verification() {
+        use w3f_plonk_common::batch_verifier::PlonkBatchVerifier;
+
+        use crate::multi_ring_batch_verifier::BatchItem;
+
+        let rng = &mut test_rng();
+
+        let (pcs_params, piop_params) = setup::<_, KZG<Bls12_381>>(rng, 2usize.pow(9));
+        let pks = random_vec::<EdwardsAffine, _>(100, rng);
+        let (prover_key, verifier_key) =
+            index::<_, KZG<Bls12_381>, _>(&pcs_params, &piop_params, &pks);
+        let column = piop_params.subset_selector(&[10, 20, 30]);
+        let commitment = KZG::<Bls12_381>::commit(&prover_key.pcs_ck, column.as_poly()).unwrap();
+        let subset_selector = SubsetSelector { column, commitment };
+
+        let prover = RingProver::init(
+            prover_key,
+            piop_params.clone(),
+            0,
+            ArkTranscript::new(b"w3f-ring-proof-test"),
+        );
+        let verifier = RingVerifier::init(
+            verifier_key,
+            piop_params,
+            ArkTranscript::new(b"w3f-ring-proof-test"),
+        );
+
+        let (result, proof) = prover.rerandomize_pk(5, Fr::rand(rng));
+        let (subset_result, subset_proof) =
+            prover.rerandomize_pk_in_subset(&subset_selector, 20, Fr::rand(rng));
+
+        let batch = |subset_result| {
+            let mut batch = PlonkBatchVerifier::new(
+                verifier.pcs_vk().clone(),
+                ArkTranscript::new(b"w3f-ring-proof-batch"),
+            );
+            batch.push_prepared(BatchItem::new(&verifier, proof.clone(), result).into_prepared());
+            batch.push_prepared(verifier.prepare_in_subset(
+                subset_proof.clone(),
+                subset_result,
+                subset_selector.commitment.clone(),
+            ));
+            batch
+        };
+        assert!(batch(subset_result).verify());
+        let batch = batch(EdwardsAffine::rand(rng));
+        assert!(!batch.verify());
+        assert_eq!(batch.verify_and_identify(), vec![1]);
+    }
+
+    #[test]
+    fn test_weighted_ring_proof() {
+        let rng = &mut test_rng();
+
+        let domain_size = 2usize.pow(9);
+        let (pcs_params, piop_params) = setup::<_, KZG<Bls12_381>>(rng, domain_size);
+        let ring_builder_key = RingBuilderKey::from_srs(&pcs_params, domain_size);
+        let srs =
+            |range: ark_std::ops::Range<usize>| Ok(ring_builder_key.lis_in_g1[range].to_vec());
+
+        let pks = random_vec::<EdwardsAffine, _>(100, rng);
+        let stakes: Vec<u64> = (0..pks.len() as u64).map(|i| i * 1000).collect();
+        let (prover_key, verifier_key) =
+            index_weighted::<_, KZG<Bls12_381>, _>(&pcs_params, &piop_params, &pks, &stakes);
+        // The weights commitments can be computed from the Lagrangian SRS alone.
+        let ring = Ring::<_, Bls12_381, _>::with_keys(&piop_params, &pks, &ring_builder_key);
+        let weighted_ring = ring.clone().with_weights(&stakes, srs);
+        assert_eq!(
+            WeightedVerifierKey::from_ring_and_kzg_vk(&weighted_ring, pcs_params.raw_vk()),
+            verifier_key
+        );
+        assert_eq!(verifier_key.id(&piop_params, pks.len()), weighted_ring.id());
+        // The weights are a part of the ring id, unlike the ring itself.
+        assert_ne!(weighted_ring.id(), ring.id());
+        let other_ring = ring.clone().with_weights(&stakes[1..], srs);
+        assert_ne!(other_ring.id(), weighted_ring.id());
+        // The encoding of the plain verifier key doesn't depend on the weights.
+        let (plain_prover_key, plain_verifier_key) =
+            index::<_, KZG<Bls12_381>, _>(&pcs_params, &piop_params, &pks);
+        let mut plain_bytes = Vec::new();
+        plain_verifier_key
+            .serialize_compressed(&mut plain_bytes)
+            .unwrap();
+        let mut bytes = Vec::new();
+        verifier_key
+            .verifier_key
+            .serialize_compressed(&mut bytes)
+            .unwrap();
+        assert_eq!(bytes, plain_bytes);
+        // The same, with the weights set on an existing key.
+        let other_prover_key = plain_prover_key.with_weights(&stakes, &piop_params, srs);
+        assert_eq!(other_prover_key.verifier_key(), verifier_key);
+        assert_eq!(
+            other_prover_key.weights.weights.evals,
+            piop_params.weights(&stakes).evals
+        );
+        let mut bytes = Vec::new();
+        prover_key.serialize_compressed(&mut bytes).unwrap();
+        let deserialized =
+            WeightedProverKey::<_, KZG<Bls12_381>, _>::deserialize_compressed(&*bytes).unwrap();
+        assert_eq!(deserialized.verifier_key(), verifier_key);
+
+        let other_verifier_key =
+            WeightedVerifierKey::from_ring_and_kzg_vk(&other_ring, pcs_params.raw_vk());
+        let other_verifier = WeightedRingVerifier::init(
+            other_verifier_key,
+            piop_params.clone(),
+            ArkTranscript::new(b"w3f-ring-proof-test"),
+        );
+        let prover = WeightedRingProver::init(
+            prover_key,
+            piop_params.clone(),
+            ArkTranscript::new(b"w3f-ring-proof-test"),
+        );
+        let verifier = WeightedRingVerifier::init(
+            verifier_key,
+            piop_params,
+            ArkTranscript::new(b"w3f-ring-proof-test"),
+        );
+
+        let (result, proof) = prover.rerandomize_pk(50_000, 50, Fr::rand(rng));
+        assert!(verifier.verify(proof.clone(), result, 50_000));
+        // The proof is bound to the threshold.
+        assert!(!verifier.verify(proof.clone(), result, 40_000));
+        // The proof is bound to the weights.
+        assert!(!other_verifier.verify(proof, result, 50_000));
+
+        // The weight is below the threshold.
+        let (result, proof) = prover.rerandomize_pk(50_000, 49, Fr::rand(rng));
+        assert!(!verifier.verify(proof, result, 50_000));
+    }
+
+    #[test]
+    fn test_many_members_proof() {
+        let rng = &mut test_rng();
+
+        let (pcs_params, piop_params) = setup::<_, KZG<Bls12_381>>(rng, 2usize.pow(9));
+        let pks = random_vec::<EdwardsAffine, _>(100, rng);
+        let (prover_key, verifier_key) =
+            index::<_, KZG<Bls12_381>, _>(&pcs_params, &piop_params, &pks);
+        let prover = RingProver::init(
+            prover_key,
+            piop_params.clone(),
+            0,
+            ArkTranscript::new(b"w3f-ring-proof-test"),
+        );
+        let verifier = RingVerifier::init(
+            verifier_key,
+            piop_params.clone(),
+            ArkTranscript::new(b"w3f-ring-proof-test"),
+        );
+
+        let claims = [(3, Fr::rand(rng)), (42, Fr::rand(rng)), (7, Fr::rand(rng))];
+        let (results, proof) = prover.rerandomize_many(claims);
+        for (&(k, r), result) in claims.iter().zip(&results) {
+            assert_eq!(*result, piop_params.blind_pk(pks[k], r));
+        }
+        assert!(verifier.verify_many(proof.clone(), results.clone()));
+
+        let mut swapped = results.clone();
+        swapped.swap(0, 1);
+        assert!(!verifier.verify_many(proof.clone(), swapped));
+        assert!(!verifier.verify_many(proof, results[..2].to_vec()));
+
+        // The same member can't be counted twice.
+        let claims = [(3, Fr::rand(rng)), (3, Fr::rand(rng))];
+        let (results, proof) = prover.rerandomize_many(claims);
+        assert!(!verifier.verify_many(proof, results));
+    }
+
+    #[test]
+    fn test_tuple_ring_proof() {
+        let rng = &mut test_rng();
+
+        let domain_size = 2usize.pow(9);
+        let pcs_params = KZG::<Bls12_381>::setup(3 * domain_size, rng);
+        let params = TupleParams::<EdwardsAffine, 2>::rand(domain_size, rng);
+        let keys: Vec<[EdwardsAffine; 2]> = (0..10)
+            .map(|_| [EdwardsAffine::rand(rng), EdwardsAffine::rand(rng)])
+            .collect();
+        let (prover_key, verifier_key) =
+            index_tuples::<_, KZG<Bls12_381>, _, 2>(&pcs_params, &params, &keys);
+
+        let ring_builder_key = RingBuilderKey::from_srs(&pcs_params, domain_size);
+        let ring = TupleRing::<_, Bls12_381, _, 2>::with_keys(&params, &keys, &ring_builder_key);
+        assert_eq!(
+            VerifierKey::from_tuple_ring_and_kzg_vk(&ring, pcs_params.raw_vk()),
+            verifier_key
+        );
+
+        let prover = TupleRingProver::init(
+            prover_key,
+            params.clone(),
+            ArkTranscript::new(b"w3f-ring-proof-test"),
+        );
+        let verifier = TupleRingVerifier::init(
+            verifier_key,
+            params.clone(),
+            ArkTranscript::new(b"w3f-ring-proof-test"),
+        );
+
+        let r = Fr::rand(rng);
+        let (results, proof) = prover.rerandomize_pks(7, r);
+        assert_eq!(results, params.blind_pks(&keys[7], r));
+        assert!(verifier.verify(proof.clone(), results.clone()));
+
+        // The keys come from different rows.
+        let mixed = vec![results[0], params.blind_pks(&keys[8], r)[1]];
+        assert!(!verifier.verify(proof.clone(), mixed));
+        assert!(!verifier.verify(proof, results[..1].to_vec()));
+    }
+
+    #[test]
+    fn test_traceable_ring_proof() {
+        use crate::tracing::{traceable_keys, traceable_params, verify_opening, Tracer};
+
+        let rng = &mut test_rng();
+
+        let domain_size = 2usize.pow(9);
+        let (pcs_params, piop_params) = setup::<_, KZG<Bls12_381>>(rng, domain_size);
+        let h = piop_params.h;
+        let tracer = Tracer::rand(h, rng);
+        let params = traceable_params(piop_params, tracer.pk());
+        let pks = random_vec::<EdwardsAffine, _>(10, rng);
+        let (prover_key, verifier_key) =
+            index_tuples::<_, KZG<Bls12_381>, _, 2>(&pcs_params, &params, &traceable_keys(&pks));
+
+        let prover = TupleRingProver::init(
+            prover_key,
+            params.clone(),
+            ArkTranscript::new(b"w3f-ring-proof-test"),
+        );
+        let verifier = TupleRingVerifier::init(
+            verifier_key,
+            params.clone(),
+            ArkTranscript::new(b"w3f-ring-proof-test"),
+        );
+
+        let r = Fr::rand(rng);
+        let (results, proof) = prover.rerandomize_pks(3, r);
+        assert_eq!(results, params.blind_pks(&[pks[3], pks[3]], r));
+        assert!(verifier.verify(proof, results.clone()));
+
+        let (pk, opening) = tracer.trace(&results).unwrap();
+        assert_eq!(pk, pks[3]);
+        assert!(verify_opening(h, tracer.pk(), &results, pk, &opening));
+        // Wrong key.
+        assert!(!verify_opening(h, tracer.pk(), &results, pks[4], &opening));
+        // Another tracer.
+        let other = Tracer::rand(h, rng);
+        let (other_pk, other_opening) = other.trace(&results).unwrap();
+        assert_ne!(other_pk, pks[3]);
+        assert!(!verify_opening(
+            h,
+            tracer.pk(),
+            &results,
+            other_pk,
+            &other_opening
+        ));
+        assert!(tracer.trace(&results[..1]).is_err());
+    }
+
+    #[test]
+    fn test_hidden_ring_proof() {
+        use ark_bls12_381::G1Projective;
+        use w3f_pcs::pcs::ipa::hiding::HidingIpa;
+
+        use crate::hidden::{
+            commit_hidden_ring, HiddenRingProver, HiddenRingVerifier, RingBlinding,
+            MAX_PROOFS_PER_SESSION,
+        };
+
+        let rng = &mut test_rng();
+
+        let (pcs_params, piop_params) = setup::<_, HidingIpa<G1Projective>>(rng, 2usize.pow(9));
+        let pks = random_vec::<EdwardsAffine, _>(10, rng);
+        let blinding = RingBlinding::rand(rng);
+        let mut ring = commit_hidden_ring(&pcs_params, &piop_params, &pks, &blinding);
+        let commitment = ring.commitment();
+
+        // Committing to the same ring in another session gives an unrelated commitment.
+        let other_commitment =
+            commit_hidden_ring(&pcs_params, &piop_params, &pks, &RingBlinding::rand(rng))
+                .commitment();
+        assert_ne!(commitment, other_commitment);
+
+        // The same ring, with wrong blinding factors.
+        let mut wrong_ring = commit_hidden_ring(&pcs_params, &piop_params, &pks, &blinding);
+        wrong_ring.blinding = RingBlinding::rand(rng);
+
+        let verifier = HiddenRingVerifier::init(
+            &pcs_params,
+            piop_params.clone(),
+            ArkTranscript::new(b"w3f-ring-proof-test"),
+        );
+        let prover = HiddenRingProver::init(
+            pcs_params,
+            piop_params.clone(),
+            ArkTranscript::new(b"w3f-ring-proof-test"),
+        );
+
+        // Wrong blinding factors.
+        let (result, proof) = prover
+            .rerandomize_pk(&mut wrong_ring, 5, Fr::rand(rng), rng)
+            .unwrap();
+        assert!(!verifier.verify(commitment.clone(), result, proof));
+
+        // Several proofs in a session, up to the limit.
+        for k in 0..MAX_PROOFS_PER_SESSION {
+            assert_eq!(ring.proofs_left(), MAX_PROOFS_PER_SESSION - k);
+            let r = Fr::rand(rng);
+            let (result, proof) = prover.rerandomize_pk(&mut ring, k, r, rng).unwrap();
+            assert_eq!(result, piop_params.blind_pk(pks[k], r));
+            assert!(verifier.verify(commitment.clone(), result, proof.clone()));
+            assert!(!verifier.verify(other_commitment.clone(), result, proof));
+        }
+        // The session is over.
+        assert_eq!(ring.proofs_left(), 0);
+        assert!(prover
+            .rerandomize_pk(&mut ring, 5, Fr::rand(rng), rng)
+            .is_err());
+    }
+
+    #[test]
+    fn test_ipa_ring_proof() {
+        use ark_pallas::Projective as Pallas;
+        use ark_vesta::{Affine as VestaAffine, Fr as VestaFr};
+        use w3f_pcs::pcs::ipa::IPA;
+
+        use crate::ipa::{IpaRing, IpaRingBuilderKey, IpaRingProver, IpaRingVerifier};
+
+        let rng = &mut test_rng();
+
+        let domain_size = 2usize.pow(9);
+        let pcs_params = IPA::<Pallas>::setup(3 * domain_size, rng);
+        let piop_params = PiopParams::<VestaAffine>::rand(domain_size, rng);
+        let keyset_size = piop_params.keyset_part_size;
+        let pks = random_vec::<VestaAffine, _>(keyset_size, rng);
+
+        let builder_key = IpaRingBuilderKey::from_ck(&pcs_params.ck(), domain_size);
+        let mut ring = IpaRing::with_keys(&piop_params, &pks[..10], &builder_key);
+        ring.append(&pks[10..], &builder_key);
+        let (_, verifier_key) = index::<_, IPA<Pallas>, _>(&pcs_params, &piop_params, &pks);
+        assert_eq!(
+            VerifierKey::from_ipa_ring(&ring, pcs_params.raw_vk()).fixed_columns_committed,
+            verifier_key.fixed_columns_committed
+        );
+
+        let prover_key = ProverKey::from_ipa_ring(&ring, &pks, &piop_params, &pcs_params);
+        let prover = IpaRingProver::init(
+            prover_key,
+            piop_params.clone(),
+            0,
+            ArkTranscript::new(b"w3f-ring-proof-test"),
+        );
+        let verifier = IpaRingVerifier::init(
+            verifier_key,
+            piop_params.clone(),
+            ArkTranscript::new(b"w3f-ring-proof-test"),
+        );
+
+        let (results, proofs): (Vec<_>, Vec<_>) = (0..3)
+            .map(|_| {
+                let k = rng.gen_range(0..keyset_size);
+                let r = VestaFr::rand(rng);
+                let (result, proof) = prover.rerandomize_pk(k, r);
+                assert_eq!(result, piop_params.blind_pk(pks[k], r));
+                (result, proof)
+            })
+            .unzip();
+        assert!(verifier.verify(proofs[0].clone(), results[0]));
+        assert!(verifier.verify_batch_ipa(proofs.clone(), results.clone()));
+
+        let mut wrong_results = results.clone();
+        wrong_results[1] = VestaAffine::rand(rng);
+        assert!(!verifier.verify(proofs[1].clone(), wrong_results[1]));
+        assert!(!verifier.verify_batch_ipa(proofs.clone(), wrong_results));
+        assert!(!verifier.verify_batch_ipa(proofs, results[1..].to_vec()));
+    }
+
+    #[test]
+    fn test_ipa_batch() {
+        use ark_pallas::Projective as Pallas;
+        use ark_vesta::{Affine as VestaAffine, Fr as VestaFr};
+        use w3f_pcs::pcs::ipa::IPA;
+        use w3f_plonk_common::ipa_batch::IpaBatch;
+
+        use crate::ipa::{IpaRingProof, IpaRingProver, IpaRingVerifier};
+
+        let rng = &mut test_rng();
+
+        let domain_size = 2usize.pow(9);
+        let pcs_params = IPA::<Pallas>::setup(3 * domain_size, rng);
+        let piop_params = PiopParams::<VestaAffine>::rand(domain_size, rng);
+        let pks = random_vec::<VestaAffine, _>(piop_params.keyset_part_size, rng);
+        let (prover_key, verifier_key) =
+            index::<_, IPA<Pallas>, _>(&pcs_params, &piop_params, &pks);
+        let prover = IpaRingProver::init(
+            prover_key,
+            piop_params.clone(),
+            0,
+            ArkTranscript::new(b"w3f-ring-proof-test"),
+        );
+        let verifier = IpaRingVerifier::init(
+            verifier_key,
+            piop_params,
+            ArkTranscript::new(b"w3f-ring-proof-test"),
+        );
+
+        let claims: Vec<_> = (0..4)
+            .map(|k| prover.rerandomize_pk(k, VestaFr::rand(rng)))
+            .collect();
+        let batch_of = |claims: &[(VestaAffine, IpaRingProof<Pallas>)]| {
+            let mut batch = IpaBatch::new(verifier.pcs_vk().clone());
+            for (result, proof) in claims {
+                batch.push_claims(verifier.opening_claims(proof.clone(), *result).0);
+            }
+            batch
+        };
+
+        let batch = batch_of(&claims);
+        // Every proof is reduced to 2 openings.
+        assert_eq!(batch.len(), 2 * claims.len());
+        assert!(batch.verify(rng));
+
+        // A bad proof fails the whole batch.
+        let mut bad_claims = claims.clone();
+        bad_claims[2].0 = VestaAffine::rand(rng);
+        assert!(!batch_of(&bad_claims).verify(rng));
+    }
+
     pub fn setup<R: Rng, CS: PCS<Fq>>(
         rng: &mut R,
         domain_size: usize,
@@ -203,6 +993,129 @@ mod tests {
         end_timer!(t_batch_verify);
     }
 
+    #[test]
+    fn test_ring_proof_batch_kzg_par_verification() {
+        let rng = &mut test_rng();
+        let (verifier, claims) = _test_ring_proof::<KZG<Bls12_381>>(2usize.pow(9), 5);
+        let (mut results, proofs): (Vec<_>, Vec<_>) = claims.into_iter().unzip();
+        assert!(verifier.verify_batch_kzg_par(proofs.clone(), results.clone()));
+
+        results[2] = EdwardsAffine::rand(rng);
+        assert!(!verifier.verify_batch_kzg(proofs.clone(), results.clone()));
+        assert!(!verifier.verify_batch_kzg_par(proofs.clone(), results.clone()));
+        assert!(!verifier.verify_batch_kzg_par(proofs, results[1..].to_vec()));
+    }
+
+    #[test]
+    fn test_sharded_batch_verification() {
+        use ark_serialize::{Compress, Validate};
+        use w3f_plonk_common::kzg_acc::KzgAccumulator;
+
+        use crate::multi_ring_batch_verifier::BatchVerifier;
+
+        let rng = &mut test_rng();
+        let (verifier, claims) = _test_ring_proof::<KZG<Bls12_381>>(2usize.pow(9), 4);
+        let shard = |claims: &[(EdwardsAffine, RingProof<Fq, KZG<Bls12_381>>)]| {
+            let mut batch = BatchVerifier::new(
+                verifier.pcs_vk().clone(),
+                verifier.plonk_verifier.transcript_prelude.clone(),
+            );
+            for (result, proof) in claims.iter().cloned() {
+                batch.push(&verifier, proof, result);
+            }
+            batch.into_accumulator()
+        };
+
+        let mut acc = shard(&claims[..2]);
+        let other_acc = shard(&claims[2..]);
+        let mut buf = vec![];
+        other_acc.serialize_compressed(&mut buf).unwrap();
+        let other_acc = KzgAccumulator::deserialize_with_vk(
+            &buf[..],
+            Compress::Yes,
+            Validate::Yes,
+            verifier.pcs_vk().clone(),
+        )
+        .unwrap();
+        assert!(acc.merge(other_acc).is_ok());
+        assert_eq!(acc.len(), 4);
+        assert!(acc.verify());
+        assert!(KzgAccumulator::verify_finalized(
+            acc.finalize(),
+            verifier.pcs_vk()
+        ));
+
+        let mut bad_claims = claims.clone();
+        bad_claims[3].0 = EdwardsAffine::rand(rng);
+        let mut acc = shard(&claims[..2]);
+        assert!(acc.merge(shard(&bad_claims[2..])).is_ok());
+        assert!(!acc.verify());
+        assert_eq!(acc.verify_and_identify(), vec![3]);
+
+        // An accumulator for another key can't be merged.
+        let other_vk = KZG::<Bls12_381>::setup(0, rng).vk();
+        let mut other_acc = KzgAccumulator::new(other_vk);
+        assert!(other_acc.merge(shard(&claims[..2])).is_err());
+
+        // The items are serialized last, as `(acc_terms, g1_scalar, proofs)` each.
+        let acc = shard(&claims);
+        let mut buf = vec![];
+        acc.serialize_compressed(&mut buf).unwrap();
+        let item_size = 4 * 8 + Fq::default().compressed_size();
+        let items_at = buf.len() - claims.len() * item_size;
+        let deserialize = |buf: &[u8]| {
+            KzgAccumulator::deserialize_with_vk(
+                buf,
+                Compress::Yes,
+                Validate::Yes,
+                verifier.pcs_vk().clone(),
+            )
+        };
+        assert!(deserialize(&buf).is_ok());
+        // The first item covers the shared `G1` term.
+        let mut bad_buf = buf.clone();
+        bad_buf[items_at..items_at + 8].copy_from_slice(&0u64.to_le_bytes());
+        assert!(deserialize(&bad_buf).is_err());
+        // The second item overlaps the first one.
+        let mut bad_buf = buf.clone();
+        let first_start = buf[items_at..items_at + 8].to_vec();
+        bad_buf[items_at + item_size..items_at + item_size + 8].copy_from_slice(&first_start);
+        assert!(deserialize(&bad_buf).is_err());
+    }
+
+    #[test]
+    fn test_batch_verify_and_identify() {
+        let rng = &mut test_rng();
+        let (verifier, claims) = _test_ring_proof::<KZG<Bls12_381>>(2usize.pow(9), 5);
+        let (mut results, proofs): (Vec<_>, Vec<_>) = claims.into_iter().unzip();
+        assert_eq!(
+            verifier.verify_batch_kzg_and_identify(proofs.clone(), results.clone()),
+            Ok(vec![])
+        );
+
+        results[1] = EdwardsAffine::rand(rng);
+        results[3] = EdwardsAffine::rand(rng);
+        assert!(!verifier.verify_batch_kzg(proofs.clone(), results.clone()));
+        assert_eq!(
+            verifier.verify_batch_kzg_and_identify(proofs.clone(), results.clone()),
+            Ok(vec![1, 3])
+        );
+        assert!(verifier
+            .verify_batch_kzg_and_identify(proofs.clone(), results[1..].to_vec())
+            .is_err());
+
+        use crate::multi_ring_batch_verifier::BatchVerifier;
+        let mut batch = BatchVerifier::new(
+            verifier.pcs_vk().clone(),
+            verifier.plonk_verifier.transcript_prelude.clone(),
+        );
+        for (proof, result) in proofs.into_iter().zip(results) {
+            batch.push(&verifier, proof, result);
+        }
+        assert!(!batch.verify());
+        assert_eq!(batch.verify_and_identify(), vec![1, 3]);
+    }
+
     #[test]
     fn test_multi_ring_batch_verify_kzg() {
         let rng = &mut test_rng();
@@ -280,4 +1193,64 @@ mod tests {
         }
         assert!(batch.verify());
     }
+
+    #[test]
+    fn test_ring_verifier_registry() {
+        use crate::registry::RingVerifierRegistry;
+
+        let rng = &mut test_rng();
+        let (pcs_params, piop_params) = setup::<_, KZG<Bls12_381>>(rng, 2usize.pow(9));
+        let mut registry = RingVerifierRegistry::new(
+            pcs_params.raw_vk(),
+            piop_params.clone(),
+            ArkTranscript::new(b"w3f-ring-proof-test"),
+            2,
+        );
+
+        let ring_builder_key = RingBuilderKey::from_srs(&pcs_params, 2usize.pow(9));
+        let mut rings = (0..3).map(|_| {
+            let pks = random_vec::<EdwardsAffine, _>(10, rng);
+            let (prover_key, _) = index::<_, KZG<Bls12_381>, _>(&pcs_params, &piop_params, &pks);
+            let prover = RingProver::init(
+                prover_key,
+                piop_params.clone(),
+                3,
+                ArkTranscript::new(b"w3f-ring-proof-test"),
+            );
+            let (result, proof) = prover.rerandomize_pk(3, Fr::rand(rng));
+            let ring = Ring::with_keys(&piop_params, &pks, &ring_builder_key);
+            (ring, result, proof)
+        });
+        let (ring_a, result_a, proof_a) = rings.next().unwrap();
+        let (ring_b, result_b, proof_b) = rings.next().unwrap();
+        let (ring_c, result_c, proof_c) = rings.next().unwrap();
+
+        let id_a = registry.register(&ring_a);
+        let id_b = registry.register(&ring_b);
+        assert_eq!(registry.register(&ring_a), id_a);
+        assert_eq!(id_a, ring_a.id());
+        assert_eq!(registry.len(), 2);
+
+        assert!(registry.verify(&id_a, proof_a.clone(), result_a));
+        assert!(!registry.verify(&id_b, proof_a.clone(), result_a));
+
+        let mut batch = registry.new_batch();
+        registry
+            .push(&mut batch, &id_a, proof_a.clone(), result_a)
+            .unwrap();
+        registry
+            .push(&mut batch, &id_b, proof_b.clone(), result_b)
+            .unwrap();
+        assert!(batch.verify());
+
+        // `a` is the least recently used ring now.
+        let id_c = registry.register(&ring_c);
+        assert_eq!(registry.len(), 2);
+        assert!(!registry.contains(&id_a));
+        assert!(registry.contains(&id_b));
+        assert!(registry.verify(&id_c, proof_c, result_c));
+        let mut batch = registry.new_batch();
+        assert!(registry.push(&mut batch, &id_a, proof_a, result_a).is_err());
+        assert!(batch.is_empty());
+    }
 }
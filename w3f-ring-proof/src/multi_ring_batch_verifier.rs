@@ -1,17 +1,33 @@
 use ark_ec::pairing::Pairing;
 use ark_ec::twisted_edwards::{Affine, TECurveConfig};
 use ark_ec::CurveGroup;
-use ark_std::rand::RngCore;
+use ark_std::vec::Vec;
 use w3f_pcs::pcs::kzg::params::KzgVerifierKey;
 use w3f_pcs::pcs::kzg::KZG;
 use w3f_pcs::pcs::PCS;
+use w3f_plonk_common::batch_verifier::{PlonkBatchVerifier, PreparedItem};
 use w3f_plonk_common::kzg_acc::KzgAccumulator;
 use w3f_plonk_common::transcript::PlonkTranscript;
-use w3f_plonk_common::verifier::Challenges;
+use w3f_plonk_common::verifier::PlonkVerifier;
 
-use crate::piop::PiopVerifier;
+use crate::piop::{FixedColumnsCommitted, PiopVerifier, RingCommitments, RingEvaluations};
 use crate::ring_verifier::RingVerifier;
+use crate::PiopParams;
 use crate::RingProof;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+/// A ring proof prepared for batch verification, see `PreparedItem`.
+pub type PreparedRingProof<E, J> = PreparedItem<
+    E,
+    PiopVerifier<
+        <E as Pairing>::ScalarField,
+        <KZG<E> as PCS<<E as Pairing>::ScalarField>>::C,
+        Affine<J>,
+    >,
+    RingCommitments<<E as Pairing>::ScalarField, <KZG<E> as PCS<<E as Pairing>::ScalarField>>::C>,
+    RingEvaluations<<E as Pairing>::ScalarField>,
+>;
 
 /// A ring proof preprocessed for multi-ring batch verification.
 pub struct BatchItem<E, J>
@@ -19,10 +35,7 @@ where
     E: Pairing,
     J: TECurveConfig<BaseField = E::ScalarField>,
 {
-    piop: PiopVerifier<E::ScalarField, <KZG<E> as PCS<E::ScalarField>>::C, Affine<J>>,
-    proof: RingProof<E::ScalarField, KZG<E>>,
-    challenges: Challenges<E::ScalarField>,
-    entropy: [u8; 32],
+    item: PreparedRingProof<E, J>,
 }
 
 impl<E, J> BatchItem<E, J>
@@ -43,30 +56,53 @@ where
     where
         T: PlonkTranscript<E::ScalarField, KZG<E>>,
     {
-        let (challenges, mut fs_rng) = verifier
-            .plonk_verifier
-            .restore_fs_with_rng::<PiopVerifier<_, _, Affine<J>>, _, _>(&result, &proof);
-        let seed = verifier.piop_params.seed;
+        Self::from_parts(
+            &verifier.plonk_verifier.transcript_prelude,
+            &verifier.piop_params,
+            &verifier.fixed_columns_committed,
+            proof,
+            result,
+        )
+    }
+
+    // Same as `new`, for a verifier assembled from the shared and the per-ring parts,
+    // see `RingVerifierRegistry`. `transcript_prelude` is the one of the ring.
+    pub(crate) fn from_parts<T>(
+        transcript_prelude: &T,
+        piop_params: &PiopParams<Affine<J>>,
+        fixed_columns_committed: &FixedColumnsCommitted<
+            E::ScalarField,
+            <KZG<E> as PCS<E::ScalarField>>::C,
+        >,
+        proof: RingProof<E::ScalarField, KZG<E>>,
+        result: Affine<J>,
+    ) -> Self
+    where
+        T: PlonkTranscript<E::ScalarField, KZG<E>>,
+    {
+        let (challenges, mut fs_rng) = PlonkVerifier::<_, KZG<E>, T>::restore_fs_with_prelude::<
+            PiopVerifier<_, _, Affine<J>>,
+            _,
+            _,
+        >(transcript_prelude, &[result], &proof);
+        let seed = piop_params.seed;
         let seed_plus_result = (seed + result).into_affine();
-        let domain_at_zeta = verifier.piop_params.domain.evaluate(challenges.zeta);
+        let domain_at_zeta = piop_params.domain.evaluate(challenges.zeta);
         let piop = PiopVerifier::<_, _, Affine<J>>::init(
             domain_at_zeta,
-            verifier.fixed_columns_committed.clone(),
+            fixed_columns_committed.clone(),
             proof.column_commitments.clone(),
             proof.columns_at_zeta.clone(),
             (seed.x, seed.y),
-            (seed_plus_result.x, seed_plus_result.y),
+            [(seed_plus_result.x, seed_plus_result.y)],
         );
+        let item = PreparedItem::new(piop, proof, challenges, &mut fs_rng);
+        Self { item }
+    }
 
-        let mut entropy = [0_u8; 32];
-        fs_rng.fill_bytes(&mut entropy);
-
-        Self {
-            piop,
-            proof,
-            challenges,
-            entropy,
-        }
+    /// Returns the item to be verified in a batch with proofs of other PIOPs, see `PlonkBatchVerifier`.
+    pub fn into_prepared(self) -> PreparedRingProof<E, J> {
+        self.item
     }
 }
 
@@ -74,19 +110,12 @@ where
 ///
 /// Accumulates proofs from one or more rings (keysets) into a single batched
 /// pairing check. All rings must share the same KZG SRS.
-///
-/// Holds its own transcript instance, cloned on each `push_prepared` call so
-/// the per-proof entropy can be folded in without touching the originating
-/// `RingVerifier`. Per-proof independence is ensured by the entropy derived
-/// during preparation (which absorbs the full proof via the per-ring
-/// transcript), so the base transcript only needs to be deterministic, not
-/// proof-specific.
+/// A thin wrapper around `PlonkBatchVerifier`, that prepares ring proofs.
 pub struct BatchVerifier<E: Pairing, T>
 where
     T: PlonkTranscript<E::ScalarField, KZG<E>>,
 {
-    acc: KzgAccumulator<E>,
-    transcript: T,
+    batch: PlonkBatchVerifier<E, T>,
 }
 
 impl<E: Pairing, T> BatchVerifier<E, T>
@@ -96,8 +125,7 @@ where
     /// Creates a new multi-ring batch verifier.
     pub fn new(kzg_vk: KzgVerifierKey<E>, transcript: T) -> Self {
         Self {
-            acc: KzgAccumulator::<E>::new(kzg_vk),
-            transcript,
+            batch: PlonkBatchVerifier::new(kzg_vk, transcript),
         }
     }
 
@@ -125,14 +153,82 @@ where
     where
         J: TECurveConfig<BaseField = E::ScalarField>,
     {
-        let mut ts = self.transcript.clone();
-        ts._add_serializable(b"batch-entropy", &item.entropy);
-        self.acc
-            .accumulate(item.piop, item.proof, item.challenges, &mut ts.to_rng());
+        self.batch.push_prepared(item.item);
+    }
+
+    /// Adds many ring proofs, possibly from different rings, to the batch.
+    ///
+    /// With the `parallel` feature on, the items are prepared in parallel,
+    /// and then accumulated in parallel chunks, that are merged in order.
+    /// The result of the verification is the same as of pushing the proofs one by one.
+    pub fn push_many<J>(
+        &mut self,
+        items: Vec<(
+            &RingVerifier<E::ScalarField, KZG<E>, J, T>,
+            RingProof<E::ScalarField, KZG<E>>,
+            Affine<J>,
+        )>,
+    ) where
+        J: TECurveConfig<BaseField = E::ScalarField>,
+        RingVerifier<E::ScalarField, KZG<E>, J, T>: Sync,
+        RingProof<E::ScalarField, KZG<E>>: Send,
+        BatchItem<E, J>: Send,
+        PlonkBatchVerifier<E, T>: Send + Sync,
+    {
+        let items: Vec<BatchItem<E, J>> = ark_std::cfg_into_iter!(items)
+            .map(|(verifier, proof, result)| BatchItem::new(verifier, proof, result))
+            .collect();
+
+        #[cfg(feature = "parallel")]
+        {
+            let chunk_size = items.len().div_ceil(rayon::current_num_threads()).max(1);
+            let batches: Vec<PlonkBatchVerifier<E, T>> = items
+                .into_par_iter()
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    let mut batch = self.batch.fork();
+                    for item in chunk {
+                        batch.push_prepared(item.item);
+                    }
+                    batch
+                })
+                .collect();
+            for batch in batches {
+                // The batches are forked from this one, so share the key.
+                self.batch.merge(batch).unwrap();
+            }
+        }
+
+        #[cfg(not(feature = "parallel"))]
+        for item in items {
+            self.push_prepared(item);
+        }
+    }
+
+    /// Number of the proofs in the batch.
+    pub fn len(&self) -> usize {
+        self.batch.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.batch.is_empty()
+    }
+
+    /// Returns the accumulator, e.g. to be serialized and merged with the accumulators of other shards,
+    /// see `KzgAccumulator::merge`.
+    pub fn into_accumulator(self) -> KzgAccumulator<E> {
+        self.batch.into_accumulator()
     }
 
     /// Verifies all accumulated proofs in a single batched pairing check.
     pub fn verify(&self) -> bool {
-        self.acc.verify()
+        self.batch.verify()
+    }
+
+    /// Returns the indices of the failing proofs, in the order they were pushed, empty if the batch verifies.
+    /// Bisects the batch, so costs `O(f.log(n))` pairings for `f` failing proofs out of `n`,
+    /// see `KzgAccumulator::verify_and_identify`.
+    pub fn verify_and_identify(&self) -> Vec<usize> {
+        self.batch.verify_and_identify()
     }
 }
@@ -0,0 +1,273 @@
+//! Variant of the ring PIOP proving membership of `K` distinct keys of the ring at once,
+//! e.g. for threshold approvals.
+//!
+//! Every member gets its own bits column, with its own blinding factor in the scalar part,
+//! and its own `CondAdd` accumulator over the shared points column. Then the bits columns
+//! are constrained to highlight disjoint sets of ring rows.
+
+use ark_ec::AffineRepr;
+use ark_ff::PrimeField;
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::Evaluations;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{array, vec, vec::Vec};
+use w3f_pcs::pcs::Commitment;
+
+use w3f_plonk_common::domain::{Domain, EvaluatedDomain};
+use w3f_plonk_common::gadgets::disjointness::{Disjointness, DisjointnessValues};
+use w3f_plonk_common::gadgets::VerifierGadget;
+use w3f_plonk_common::piop::{ProverPiop, VerifierPiop};
+use w3f_plonk_common::{ColumnsCommited, ColumnsEvaluated};
+
+use crate::piop::params::PiopParams;
+use crate::piop::{
+    FixedColumns, FixedColumnsCommitted, PiopProver, PiopVerifier, RingCommitments, RingEvaluations,
+};
+
+// Number of the columns shared by the members: the points and the ring selector.
+const N_SHARED_COLUMNS: usize = 3;
+
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ManyRingCommitments<F: PrimeField, C: Commitment<F>, const K: usize> {
+    pub(crate) members: [RingCommitments<F, C>; K],
+}
+
+impl<F: PrimeField, C: Commitment<F>, const K: usize> ColumnsCommited<F, C>
+    for ManyRingCommitments<F, C, K>
+{
+    fn to_vec(self) -> Vec<C> {
+        self.members.into_iter().flat_map(|c| c.to_vec()).collect()
+    }
+}
+
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ManyRingEvaluations<F: PrimeField, const K: usize> {
+    pub(crate) points: [F; 2],
+    pub(crate) ring_selector: F,
+    pub(crate) bits: [F; K],
+    pub(crate) inn_prod_acc: [F; K],
+    pub(crate) cond_add_acc: [[F; 2]; K],
+}
+
+impl<F: PrimeField, const K: usize> ManyRingEvaluations<F, K> {
+    // Evaluations of the columns of the `i`-th member.
+    fn member(&self, i: usize) -> RingEvaluations<F> {
+        RingEvaluations {
+            points: [self.points],
+            ring_selector: self.ring_selector,
+            bits: self.bits[i],
+            inn_prod_acc: self.inn_prod_acc[i],
+            cond_add_acc: [self.cond_add_acc[i]],
+        }
+    }
+}
+
+impl<F: PrimeField, const K: usize> ColumnsEvaluated<F> for ManyRingEvaluations<F, K> {
+    fn to_vec(self) -> Vec<F> {
+        let mut evals = vec![self.points[0], self.points[1], self.ring_selector];
+        for i in 0..K {
+            evals.extend([
+                self.bits[i],
+                self.inn_prod_acc[i],
+                self.cond_add_acc[i][0],
+                self.cond_add_acc[i][1],
+            ]);
+        }
+        evals
+    }
+}
+
+pub struct ManyPiopProver<F: PrimeField, G: AffineRepr<BaseField = F>, const K: usize> {
+    members: [PiopProver<F, G>; K],
+    disjointness: Disjointness<F>,
+}
+
+impl<F: PrimeField, G: AffineRepr<BaseField = F>, const K: usize> ManyPiopProver<F, G, K> {
+    pub fn build(
+        params: &PiopParams<G>,
+        fixed_columns: FixedColumns<F, G>,
+        claims: [(usize, G::ScalarField); K],
+    ) -> Self {
+        assert!(K > 0);
+        let members = claims.map(|(prover_index_in_keys, secret)| {
+            PiopProver::build(params, fixed_columns.clone(), prover_index_in_keys, secret)
+        });
+        let bits = members.iter().map(|member| member.bits().clone()).collect();
+        let disjointness = Disjointness::init(bits, members[0].ring_selector().clone());
+        Self {
+            members,
+            disjointness,
+        }
+    }
+}
+
+impl<F, C, G, const K: usize> ProverPiop<F, C> for ManyPiopProver<F, G, K>
+where
+    F: PrimeField,
+    C: Commitment<F>,
+    G: AffineRepr<BaseField = F>,
+    PiopProver<F, G>: ProverPiop<
+        F,
+        C,
+        Commitments = RingCommitments<F, C>,
+        Evaluations = RingEvaluations<F>,
+        Instance = [G; 1],
+    >,
+{
+    // The shared columns are counted once.
+    const N_COLUMNS: usize = N_SHARED_COLUMNS
+        + K * (<PiopProver<F, G> as ProverPiop<F, C>>::N_COLUMNS - N_SHARED_COLUMNS);
+    const N_CONSTRAINTS: usize = K * <PiopProver<F, G> as ProverPiop<F, C>>::N_CONSTRAINTS + 1;
+
+    type Commitments = ManyRingCommitments<F, C, K>;
+    type Evaluations = ManyRingEvaluations<F, K>;
+    type Instance = Vec<G>;
+
+    fn committed_columns<Fun: Fn(&DensePolynomial<F>) -> C>(
+        &self,
+        commit: Fun,
+    ) -> Self::Commitments {
+        let members = array::from_fn(|i| self.members[i].committed_columns(&commit));
+        ManyRingCommitments { members }
+    }
+
+    fn columns(&self) -> Vec<DensePolynomial<F>> {
+        let mut columns = ProverPiop::<F, C>::columns(&self.members[0]);
+        for member in &self.members[1..] {
+            let member_columns = ProverPiop::<F, C>::columns(member);
+            columns.extend_from_slice(&member_columns[N_SHARED_COLUMNS..]);
+        }
+        columns
+    }
+
+    fn columns_evaluated(&self, zeta: &F) -> Self::Evaluations {
+        let members: [RingEvaluations<F>; K] =
+            array::from_fn(|i| ProverPiop::<F, C>::columns_evaluated(&self.members[i], zeta));
+        ManyRingEvaluations {
+            points: members[0].points[0],
+            ring_selector: members[0].ring_selector,
+            bits: array::from_fn(|i| members[i].bits),
+            inn_prod_acc: array::from_fn(|i| members[i].inn_prod_acc),
+            cond_add_acc: array::from_fn(|i| members[i].cond_add_acc[0]),
+        }
+    }
+
+    fn constraints(&self) -> Vec<Evaluations<F>> {
+        let mut constraints: Vec<_> = self
+            .members
+            .iter()
+            .flat_map(|member| ProverPiop::<F, C>::constraints(member))
+            .collect();
+        constraints.extend(self.disjointness.constraints());
+        constraints
+    }
+
+    fn constraints_lin(&self, zeta: &F) -> Vec<DensePolynomial<F>> {
+        let mut constraints: Vec<_> = self
+            .members
+            .iter()
+            .flat_map(|member| ProverPiop::<F, C>::constraints_lin(member, zeta))
+            .collect();
+        constraints.extend(self.disjointness.constraints_linearized(zeta));
+        constraints
+    }
+
+    fn domain(&self) -> &Domain<F> {
+        ProverPiop::<F, C>::domain(&self.members[0])
+    }
+
+    fn result(&self) -> Self::Instance {
+        self.members
+            .iter()
+            .map(|member| ProverPiop::<F, C>::result(member)[0])
+            .collect()
+    }
+}
+
+pub struct ManyPiopVerifier<
+    F: PrimeField,
+    C: Commitment<F>,
+    P: AffineRepr<BaseField = F>,
+    const K: usize,
+> {
+    members: [PiopVerifier<F, C, P>; K],
+    disjointness: DisjointnessValues<F>,
+}
+
+impl<F: PrimeField, C: Commitment<F>, P: AffineRepr<BaseField = F>, const K: usize>
+    ManyPiopVerifier<F, C, P, K>
+{
+    pub fn init(
+        domain_evals: EvaluatedDomain<F>,
+        fixed_columns_committed: FixedColumnsCommitted<F, C>,
+        witness_columns_committed: ManyRingCommitments<F, C, K>,
+        all_columns_evaluated: ManyRingEvaluations<F, K>,
+        init: (F, F),
+        results: [(F, F); K],
+    ) -> Self {
+        let disjointness = DisjointnessValues {
+            bits: all_columns_evaluated.bits.to_vec(),
+            selector: all_columns_evaluated.ring_selector,
+        };
+        let mut witness_columns_committed = witness_columns_committed.members.into_iter();
+        let members = array::from_fn(|i| {
+            PiopVerifier::init(
+                domain_evals.clone(),
+                fixed_columns_committed.clone(),
+                witness_columns_committed.next().unwrap(),
+                all_columns_evaluated.member(i),
+                init,
+                [results[i]],
+            )
+        });
+        Self {
+            members,
+            disjointness,
+        }
+    }
+}
+
+impl<F, C, P, const K: usize> VerifierPiop<F, C> for ManyPiopVerifier<F, C, P, K>
+where
+    F: PrimeField,
+    C: Commitment<F>,
+    P: AffineRepr<BaseField = F>,
+    PiopVerifier<F, C, P>: VerifierPiop<F, C, Instance = [P; 1]>,
+{
+    const N_COLUMNS: usize = N_SHARED_COLUMNS
+        + K * (<PiopVerifier<F, C, P> as VerifierPiop<F, C>>::N_COLUMNS - N_SHARED_COLUMNS);
+    const N_CONSTRAINTS: usize =
+        K * <PiopVerifier<F, C, P> as VerifierPiop<F, C>>::N_CONSTRAINTS + 1;
+    type Instance = Vec<P>;
+
+    fn precommitted_columns(&self) -> Vec<C> {
+        self.members[0].precommitted_columns()
+    }
+
+    fn evaluate_constraints_main(&self) -> Vec<F> {
+        let mut constraints: Vec<_> = self
+            .members
+            .iter()
+            .flat_map(|member| member.evaluate_constraints_main())
+            .collect();
+        constraints.extend(self.disjointness.evaluate_constraints_main());
+        constraints
+    }
+
+    fn lin_poly_commitment(&self, agg_coeffs: &[F]) -> (Vec<F>, Vec<C>) {
+        assert_eq!(agg_coeffs.len(), Self::N_CONSTRAINTS);
+        let n = <PiopVerifier<F, C, P> as VerifierPiop<F, C>>::N_CONSTRAINTS;
+        // The disjointness constraint doesn't contribute to the linearization polynomial.
+        let (coeffs, commitments): (Vec<Vec<F>>, Vec<Vec<C>>) = self
+            .members
+            .iter()
+            .zip(agg_coeffs.chunks_exact(n))
+            .map(|(member, agg_coeffs)| member.lin_poly_commitment(agg_coeffs))
+            .unzip();
+        (coeffs.concat(), commitments.concat())
+    }
+
+    fn domain_evaluated(&self) -> &EvaluatedDomain<F> {
+        self.members[0].domain_evaluated()
+    }
+}
@@ -1,97 +1,120 @@
 use ark_ec::pairing::Pairing;
 use ark_ec::{AffineRepr, CurveGroup};
 use ark_ff::PrimeField;
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+};
 use ark_std::marker::PhantomData;
-use ark_std::{vec, vec::Vec};
+use ark_std::ops::Range;
+use ark_std::{array, vec, vec::Vec};
 use w3f_pcs::pcs::commitment::WrappedAffine;
 use w3f_pcs::pcs::kzg::params::RawKzgVerifierKey;
+use w3f_pcs::pcs::kzg::urs::URS;
 use w3f_pcs::pcs::kzg::KZG;
 use w3f_pcs::pcs::{Commitment, PcsParams, PCS};
 
 pub(crate) use prover::PiopProver;
 pub(crate) use verifier::PiopVerifier;
-use w3f_plonk_common::gadgets::ec::AffineColumn;
-use w3f_plonk_common::{ColumnsCommited, ColumnsEvaluated, FieldColumn};
+use w3f_plonk_common::domain::Domain;
+use w3f_plonk_common::gadgets::ec::{AffineColumn, CompactAffineColumn};
+use w3f_plonk_common::{Column, ColumnsCommited, ColumnsEvaluated, CompactColumn, FieldColumn};
 
-use crate::ring::Ring;
+use crate::piop::params::TupleParams;
+use crate::piop::weighted::{WeightedProverKey, WeightedVerifierKey, WeightsCommitted};
+use crate::ring::{ring_id, Ring, RingId, TupleRing};
 use crate::PiopParams;
 
+pub mod many;
 pub mod params;
 pub mod prover;
+pub mod subset;
 pub mod verifier;
+pub mod weighted;
 
+// `K` is the width of the key tuples the ring is built of, see `TupleParams`.
+// The columns and the commitments specific to a tuple component come in arrays of length `K`,
+// that serialize the same as a single element for the plain ring, `K = 1`.
 #[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
-pub struct RingCommitments<F: PrimeField, C: Commitment<F>> {
+pub struct RingCommitments<F: PrimeField, C: Commitment<F>, const K: usize = 1> {
     pub(crate) bits: C,
     pub(crate) inn_prod_acc: C,
-    pub(crate) cond_add_acc: [C; 2],
+    pub(crate) cond_add_acc: [[C; 2]; K],
     pub(crate) phantom: PhantomData<F>,
 }
 
-impl<F: PrimeField, C: Commitment<F>> ColumnsCommited<F, C> for RingCommitments<F, C> {
+impl<F: PrimeField, C: Commitment<F>, const K: usize> ColumnsCommited<F, C>
+    for RingCommitments<F, C, K>
+{
     fn to_vec(self) -> Vec<C> {
-        vec![
-            self.bits,
-            self.inn_prod_acc,
-            self.cond_add_acc[0].clone(),
-            self.cond_add_acc[1].clone(),
-        ]
+        let mut commitments = vec![self.bits, self.inn_prod_acc];
+        commitments.extend(self.cond_add_acc.into_iter().flatten());
+        commitments
     }
 }
 
 #[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
-pub struct RingEvaluations<F: PrimeField> {
-    pub(crate) points: [F; 2],
+pub struct RingEvaluations<F: PrimeField, const K: usize = 1> {
+    pub(crate) points: [[F; 2]; K],
     pub(crate) ring_selector: F,
     pub(crate) bits: F,
     pub(crate) inn_prod_acc: F,
-    pub(crate) cond_add_acc: [F; 2],
+    pub(crate) cond_add_acc: [[F; 2]; K],
 }
 
-impl<F: PrimeField> ColumnsEvaluated<F> for RingEvaluations<F> {
+impl<F: PrimeField, const K: usize> ColumnsEvaluated<F> for RingEvaluations<F, K> {
     fn to_vec(self) -> Vec<F> {
-        vec![
-            self.points[0],
-            self.points[1],
-            self.ring_selector,
-            self.bits,
-            self.inn_prod_acc,
-            self.cond_add_acc[0],
-            self.cond_add_acc[1],
-        ]
+        let mut evals: Vec<F> = self.points.into_iter().flatten().collect();
+        evals.extend([self.ring_selector, self.bits, self.inn_prod_acc]);
+        evals.extend(self.cond_add_acc.into_iter().flatten());
+        evals
     }
 }
 
 // Columns commitment to which the verifier knows (or trusts).
 #[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
-pub struct FixedColumns<F: PrimeField, G: AffineRepr<BaseField = F>> {
+pub struct FixedColumns<F: PrimeField, G: AffineRepr<BaseField = F>, const K: usize = 1> {
     // Public keys of the ring participants in order,
     // followed by the powers-of-2 multiples of the second Pedersen base.
     // pk_1, ..., pk_n, H, 2H, 4H, ..., 2^sH
     // 1          n                     n+s+1
-    pub points: AffineColumn<F, G>,
+    // A column per tuple component, each with its own base `H_j`.
+    pub points: [AffineColumn<F, G>; K],
     // Binary column that highlights which rows of the table correspond to the ring.
     // 1, 1, ..., 1, 0, 0, ..., 0
     // 1          n
     pub ring_selector: FieldColumn<F>,
 }
 
+// `FixedColumns` that keep only the coefficients of the column polynomials.
+// The evaluations are recomputed on demand with `CompactFixedColumns::expand`.
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct CompactFixedColumns<F: PrimeField, G: AffineRepr<BaseField = F>> {
+    pub points: CompactAffineColumn<F, G>,
+    pub ring_selector: CompactColumn<F>,
+}
+
+impl<F: PrimeField, G: AffineRepr<BaseField = F>> CompactFixedColumns<F, G> {
+    pub fn expand(&self, domain: &Domain<F>) -> FixedColumns<F, G> {
+        FixedColumns {
+            points: [self.points.expand(domain)],
+            ring_selector: domain.expand(&self.ring_selector),
+        }
+    }
+}
+
 // Commitments to the fixed columns (see above).
 #[derive(Clone, CanonicalSerialize, CanonicalDeserialize, PartialEq, Eq, Debug)]
-pub struct FixedColumnsCommitted<F: PrimeField, C: Commitment<F>> {
-    pub points: [C; 2],
+pub struct FixedColumnsCommitted<F: PrimeField, C: Commitment<F>, const K: usize = 1> {
+    pub points: [[C; 2]; K],
     pub ring_selector: C,
     pub phantom: PhantomData<F>,
 }
 
-impl<F: PrimeField, C: Commitment<F>> FixedColumnsCommitted<F, C> {
+impl<F: PrimeField, C: Commitment<F>, const K: usize> FixedColumnsCommitted<F, C, K> {
     pub fn as_vec(&self) -> Vec<C> {
-        vec![
-            self.points[0].clone(),
-            self.points[1].clone(),
-            self.ring_selector.clone(),
-        ]
+        let mut commitments: Vec<C> = self.points.iter().flatten().cloned().collect();
+        commitments.push(self.ring_selector.clone());
+        commitments
     }
 }
 
@@ -105,19 +128,41 @@ impl<C: CurveGroup> FixedColumnsCommitted<C::ScalarField, WrappedAffine<C>> {
         let cx = WrappedAffine(ring.cx);
         let cy = WrappedAffine(ring.cy);
         Self {
-            points: [cx, cy],
+            points: [[cx, cy]],
             ring_selector: WrappedAffine(ring.selector),
             phantom: Default::default(),
         }
     }
 }
 
-impl<F: PrimeField, G: AffineRepr<BaseField = F>> FixedColumns<F, G> {
-    pub fn commit<CS: PCS<F>>(&self, ck: &CS::CK) -> FixedColumnsCommitted<F, CS::C> {
-        let points = [
-            CS::commit(ck, self.points.xs.as_poly()).unwrap(),
-            CS::commit(ck, self.points.ys.as_poly()).unwrap(),
-        ];
+impl<C: CurveGroup, const K: usize> FixedColumnsCommitted<C::ScalarField, WrappedAffine<C>, K> {
+    /// Same as `from_ring`, for a ring of `K`-tuples of keys.
+    pub fn from_tuple_ring<
+        E: Pairing<G1Affine = C::Affine>,
+        G: AffineRepr<BaseField = E::ScalarField>,
+    >(
+        ring: &TupleRing<E::ScalarField, E, G, K>,
+    ) -> Self {
+        let points = array::from_fn(|j| {
+            let ring = &ring.components[j];
+            [WrappedAffine(ring.cx), WrappedAffine(ring.cy)]
+        });
+        Self {
+            points,
+            ring_selector: WrappedAffine(ring.components[0].selector),
+            phantom: Default::default(),
+        }
+    }
+}
+
+impl<F: PrimeField, G: AffineRepr<BaseField = F>, const K: usize> FixedColumns<F, G, K> {
+    pub fn commit<CS: PCS<F>>(&self, ck: &CS::CK) -> FixedColumnsCommitted<F, CS::C, K> {
+        let points = array::from_fn(|j| {
+            [
+                CS::commit(ck, self.points[j].xs.as_poly()).unwrap(),
+                CS::commit(ck, self.points[j].ys.as_poly()).unwrap(),
+            ]
+        });
         let ring_selector = CS::commit(ck, self.ring_selector.as_poly()).unwrap();
         FixedColumnsCommitted {
             points,
@@ -127,14 +172,59 @@ impl<F: PrimeField, G: AffineRepr<BaseField = F>> FixedColumns<F, G> {
     }
 }
 
+impl<F: PrimeField, G: AffineRepr<BaseField = F>> FixedColumns<F, G> {
+    pub fn compact(&self) -> CompactFixedColumns<F, G> {
+        CompactFixedColumns {
+            points: self.points[0].compact(),
+            ring_selector: self.ring_selector.compact(),
+        }
+    }
+}
+
 #[derive(CanonicalSerialize, CanonicalDeserialize)]
-pub struct ProverKey<F: PrimeField, CS: PCS<F>, G: AffineRepr<BaseField = F>> {
+pub struct ProverKey<F: PrimeField, CS: PCS<F>, G: AffineRepr<BaseField = F>, const K: usize = 1> {
+    pub pcs_ck: CS::CK,
+    pub fixed_columns: FixedColumns<F, G, K>,
+    pub verifier_key: VerifierKey<F, CS, K>, // used in the Fiat-Shamir transform
+}
+
+impl<F: PrimeField, CS: PCS<F>, G: AffineRepr<BaseField = F>, const K: usize> Clone
+    for ProverKey<F, CS, G, K>
+{
+    fn clone(&self) -> Self {
+        Self {
+            pcs_ck: self.pcs_ck.clone(),
+            fixed_columns: self.fixed_columns.clone(),
+            verifier_key: self.verifier_key.clone(),
+        }
+    }
+}
+
+impl<F: PrimeField, CS: PCS<F>, G: AffineRepr<BaseField = F>> ProverKey<F, CS, G> {
+    /// Drops the evaluations of the fixed columns, see `CompactProverKey`.
+    pub fn compact(&self) -> CompactProverKey<F, CS, G> {
+        CompactProverKey {
+            pcs_ck: self.pcs_ck.clone(),
+            fixed_columns: self.fixed_columns.compact(),
+            verifier_key: self.verifier_key.clone(),
+        }
+    }
+}
+
+/// `ProverKey` that keeps only the coefficients of the fixed column polynomials,
+/// that is several times smaller. Suitable for long-lived provers,
+/// as the evaluations are recomputed for each proof, see `RingProver::init_compact`.
+///
+/// When deserialized with validation, the column polynomials are checked against
+/// the commitments in the embedded verifier key.
+#[derive(CanonicalSerialize)]
+pub struct CompactProverKey<F: PrimeField, CS: PCS<F>, G: AffineRepr<BaseField = F>> {
     pub pcs_ck: CS::CK,
-    pub fixed_columns: FixedColumns<F, G>,
+    pub fixed_columns: CompactFixedColumns<F, G>,
     pub verifier_key: VerifierKey<F, CS>, // used in the Fiat-Shamir transform
 }
 
-impl<F: PrimeField, CS: PCS<F>, G: AffineRepr<BaseField = F>> Clone for ProverKey<F, CS, G> {
+impl<F: PrimeField, CS: PCS<F>, G: AffineRepr<BaseField = F>> Clone for CompactProverKey<F, CS, G> {
     fn clone(&self) -> Self {
         Self {
             pcs_ck: self.pcs_ck.clone(),
@@ -144,14 +234,232 @@ impl<F: PrimeField, CS: PCS<F>, G: AffineRepr<BaseField = F>> Clone for ProverKe
     }
 }
 
+impl<F: PrimeField, CS: PCS<F>, G: AffineRepr<BaseField = F>> Valid for CompactProverKey<F, CS, G> {
+    fn check(&self) -> Result<(), SerializationError> {
+        self.pcs_ck.check()?;
+        self.fixed_columns.check()?;
+        self.verifier_key.check()?;
+        let commit = |col: &CompactColumn<F>| CS::commit(&self.pcs_ck, &col.poly).ok();
+        let committed = &self.verifier_key.fixed_columns_committed;
+        let points = &self.fixed_columns.points;
+        let matches = commit(&points.xs).as_ref() == Some(&committed.points[0][0])
+            && commit(&points.ys).as_ref() == Some(&committed.points[0][1])
+            && commit(&self.fixed_columns.ring_selector).as_ref() == Some(&committed.ring_selector);
+        if !matches {
+            return Err(SerializationError::InvalidData);
+        }
+        Ok(())
+    }
+}
+
+impl<F: PrimeField, CS: PCS<F>, G: AffineRepr<BaseField = F>> CanonicalDeserialize
+    for CompactProverKey<F, CS, G>
+{
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        let key = Self {
+            pcs_ck: CS::CK::deserialize_with_mode(&mut reader, compress, Validate::No)?,
+            fixed_columns: CompactFixedColumns::deserialize_with_mode(
+                &mut reader,
+                compress,
+                Validate::No,
+            )?,
+            verifier_key: VerifierKey::deserialize_with_mode(&mut reader, compress, Validate::No)?,
+        };
+        if let Validate::Yes = validate {
+            key.check()?;
+        }
+        Ok(key)
+    }
+}
+
+impl<F: PrimeField, CS: PCS<F>, G: AffineRepr<BaseField = F>> CompactProverKey<F, CS, G> {
+    pub fn expand(self, piop_params: &PiopParams<G>) -> ProverKey<F, CS, G> {
+        ProverKey {
+            pcs_ck: self.pcs_ck,
+            fixed_columns: self.fixed_columns.expand(&piop_params.domain),
+            verifier_key: self.verifier_key,
+        }
+    }
+}
+
+impl<F: PrimeField, CS: PCS<F>, G: AffineRepr<BaseField = F>> ProverKey<F, CS, G> {
+    /// Packs the key for storage, see `PackedProverKey`.
+    pub fn pack(&self, piop_params: &PiopParams<G>) -> PackedProverKey<F, CS, G> {
+        let points = self.fixed_columns.points[0].payload();
+        let keyset_part = &points[..piop_params.keyset_part_size];
+        // The trailing padding is restored by `PiopParams::points_column`.
+        let keys_len = keyset_part
+            .iter()
+            .rposition(|p| *p != piop_params.padding)
+            .map_or(0, |i| i + 1);
+        PackedProverKey {
+            pcs_ck: self.pcs_ck.clone(),
+            keys: keyset_part[..keys_len].to_vec(),
+            verifier_key: self.verifier_key.clone(),
+            piop_params_digest: piop_params.digest(),
+        }
+    }
+}
+
+impl<E: Pairing, G: AffineRepr<BaseField = E::ScalarField>> ProverKey<E::ScalarField, KZG<E>, G> {
+    /// Builds the prover key for a `ring` holding the `keys`, taking the commitments to the fixed columns from the ring.
+    ///
+    /// Unlike `index`, computes only the column polynomials, saving the MSMs,
+    /// as the `ring` can be maintained with the Lagrangian SRS incrementally.
+    /// With `debug_assertions` on, the commitments are recomputed and checked against the ring.
+    pub fn from_ring(
+        ring: &Ring<E::ScalarField, E, G>,
+        keys: &[G],
+        piop_params: &PiopParams<G>,
+        pcs_params: &URS<E>,
+    ) -> Self {
+        assert_eq!(keys.len(), ring.curr_keys);
+        let pcs_ck = pcs_params.ck();
+        let fixed_columns = piop_params.fixed_columns(keys);
+        let verifier_key = VerifierKey::from_ring_and_kzg_vk(ring, pcs_params.raw_vk());
+        debug_assert_eq!(
+            fixed_columns.commit::<KZG<E>>(&pcs_ck),
+            verifier_key.fixed_columns_committed,
+            "ring doesn't match the keys"
+        );
+        Self {
+            pcs_ck,
+            fixed_columns,
+            verifier_key,
+        }
+    }
+}
+
+impl<E: Pairing, G: AffineRepr<BaseField = E::ScalarField>> ProverKey<E::ScalarField, KZG<E>, G> {
+    /// Appends the keys to the ring, updating the fixed columns and the embedded verifier key in place.
+    ///
+    /// Instead of reindexing, adds multiples of the Lagrange basis polynomials to the columns,
+    /// and updates the commitments the same way `Ring::append` does,
+    /// so the verifier key stays consistent with the `Ring` tracked by the verifiers.
+    ///
+    /// - `srs`: Should return `srs[range]` for `range = (curr_keys..curr_keys + keys.len())`
+    pub fn append_keys(
+        &mut self,
+        keys: &[G],
+        piop_params: &PiopParams<G>,
+        srs: impl Fn(Range<usize>) -> Result<Vec<E::G1Affine>, ()>,
+    ) {
+        let mut ring = self.ring(piop_params);
+        let curr_keys = ring.curr_keys;
+        ring.append(keys, srs);
+        let updates: Vec<(usize, G)> = keys
+            .iter()
+            .enumerate()
+            .map(|(i, &pk)| (curr_keys + i, pk))
+            .collect();
+        self.fixed_columns.points[0].update_points(&updates, &piop_params.domain);
+        self.verifier_key.fixed_columns_committed = FixedColumnsCommitted::from_ring(&ring);
+    }
+
+    /// Replaces the key at index `i` with `pk`, see `append_keys` and `Ring::replace_key`.
+    ///
+    /// - `srs`: Should return `srs[range]` for `range = (i..i + 1)`
+    pub fn replace_key(
+        &mut self,
+        i: usize,
+        pk: G,
+        piop_params: &PiopParams<G>,
+        srs: impl Fn(Range<usize>) -> Result<Vec<E::G1Affine>, ()>,
+    ) {
+        let mut ring = self.ring(piop_params);
+        let old_key = self.fixed_columns.points[0].payload()[i];
+        ring.replace_key(i, old_key, pk, srs);
+        self.fixed_columns.points[0].update_points(&[(i, pk)], &piop_params.domain);
+        self.verifier_key.fixed_columns_committed = FixedColumnsCommitted::from_ring(&ring);
+    }
+
+    /// Makes the key weighted, committing to the weights with the Lagrangian SRS, see `Ring::with_weights`.
+    ///
+    /// - `srs`: Should return `srs[range]` for `range = (0..weights.len())` and `range = (0..WEIGHT_BITLEN)`
+    pub fn with_weights(
+        self,
+        weights: &[u64],
+        piop_params: &PiopParams<G>,
+        srs: impl Fn(Range<usize>) -> Result<Vec<E::G1Affine>, ()>,
+    ) -> WeightedProverKey<E::ScalarField, KZG<E>, G> {
+        let ring = self.ring(piop_params).with_weights(weights, srs);
+        WeightedProverKey {
+            prover_key: self,
+            weights: piop_params.ring_weights(weights),
+            weights_committed: WeightsCommitted::from_ring(&ring),
+        }
+    }
+
+    // The ring committed in the verifier key.
+    fn ring(&self, piop_params: &PiopParams<G>) -> Ring<E::ScalarField, E, G> {
+        let committed = &self.verifier_key.fixed_columns_committed;
+        let keyset_part = &self.fixed_columns.points[0].payload()[..piop_params.keyset_part_size];
+        let curr_keys = keyset_part
+            .iter()
+            .position(|p| *p == piop_params.padding)
+            .unwrap_or(keyset_part.len());
+        Ring {
+            cx: committed.points[0][0].0,
+            cy: committed.points[0][1].0,
+            selector: committed.ring_selector.0,
+            max_keys: piop_params.keyset_part_size,
+            curr_keys,
+            padding: piop_params.padding,
+        }
+    }
+}
+
+/// Storage encoding of a `ProverKey`.
+///
+/// Instead of the fixed columns with all their evaluations, keeps the keys they are computed from.
+/// `PiopParams` should be supplied to `PackedProverKey::unpack`, only their digest is stored.
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct PackedProverKey<F: PrimeField, CS: PCS<F>, G: AffineRepr<BaseField = F>> {
+    pub pcs_ck: CS::CK,
+    pub keys: Vec<G>,
+    pub verifier_key: VerifierKey<F, CS>,
+    /// `PiopParams::digest` of the parameters the key was packed under.
+    pub piop_params_digest: [u8; 32],
+}
+
+impl<F: PrimeField, CS: PCS<F>, G: AffineRepr<BaseField = F>> PackedProverKey<F, CS, G> {
+    /// Recomputes the fixed columns from the keys, and checks them against
+    /// the commitments in the embedded verifier key.
+    ///
+    /// Fails if the key was packed under different `piop_params`,
+    /// or if the commitments don't match, i.e. the key was corrupted.
+    pub fn unpack(self, piop_params: &PiopParams<G>) -> Result<ProverKey<F, CS, G>, ()> {
+        if self.piop_params_digest != piop_params.digest() {
+            return Err(());
+        }
+        if self.keys.len() > piop_params.keyset_part_size {
+            return Err(());
+        }
+        let fixed_columns = piop_params.fixed_columns(&self.keys);
+        let fixed_columns_committed = fixed_columns.commit::<CS>(&self.pcs_ck);
+        if fixed_columns_committed != self.verifier_key.fixed_columns_committed {
+            return Err(());
+        }
+        Ok(ProverKey {
+            pcs_ck: self.pcs_ck,
+            fixed_columns,
+            verifier_key: self.verifier_key,
+        })
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
-pub struct VerifierKey<F: PrimeField, CS: PCS<F>> {
+pub struct VerifierKey<F: PrimeField, CS: PCS<F>, const K: usize = 1> {
     pub pcs_raw_vk: <CS::Params as PcsParams>::RVK,
-    pub fixed_columns_committed: FixedColumnsCommitted<F, CS::C>,
+    pub fixed_columns_committed: FixedColumnsCommitted<F, CS::C, K>,
     //TODO: domain
 }
 
-impl<F: PrimeField, CS: PCS<F>> Clone for VerifierKey<F, CS> {
+impl<F: PrimeField, CS: PCS<F>, const K: usize> Clone for VerifierKey<F, CS, K> {
     fn clone(&self) -> Self {
         Self {
             pcs_raw_vk: self.pcs_raw_vk.clone(),
@@ -182,6 +490,40 @@ impl<E: Pairing> VerifierKey<E::ScalarField, KZG<E>> {
     pub fn commitment(&self) -> FixedColumnsCommitted<E::ScalarField, WrappedAffine<E::G1>> {
         self.fixed_columns_committed.clone()
     }
+
+    /// Same as `Ring::id` of the ring the key is built from.
+    /// The key doesn't store the number of keys in the ring and the padding, so they should be provided.
+    pub fn id<G: AffineRepr<BaseField = E::ScalarField>>(
+        &self,
+        piop_params: &PiopParams<G>,
+        curr_keys: usize,
+    ) -> RingId {
+        let committed = &self.fixed_columns_committed;
+        ring_id(
+            &[
+                committed.points[0][0].0,
+                committed.points[0][1].0,
+                committed.ring_selector.0,
+            ],
+            piop_params.keyset_part_size,
+            curr_keys,
+            &piop_params.padding,
+            None,
+        )
+    }
+}
+
+impl<E: Pairing, const K: usize> VerifierKey<E::ScalarField, KZG<E>, K> {
+    /// Same as `from_ring_and_kzg_vk`, for a ring of `K`-tuples of keys.
+    pub fn from_tuple_ring_and_kzg_vk<G: AffineRepr<BaseField = E::ScalarField>>(
+        ring: &TupleRing<E::ScalarField, E, G, K>,
+        kzg_vk: RawKzgVerifierKey<E>,
+    ) -> Self {
+        Self {
+            pcs_raw_vk: kzg_vk,
+            fixed_columns_committed: FixedColumnsCommitted::from_tuple_ring(ring),
+        }
+    }
 }
 
 pub fn index<F: PrimeField, CS: PCS<F>, G: AffineRepr<BaseField = F>>(
@@ -189,9 +531,50 @@ pub fn index<F: PrimeField, CS: PCS<F>, G: AffineRepr<BaseField = F>>(
     piop_params: &PiopParams<G>,
     keys: &[G],
 ) -> (ProverKey<F, CS, G>, VerifierKey<F, CS>) {
+    let fixed_columns = piop_params.fixed_columns(&keys);
+    index_columns(pcs_params, fixed_columns)
+}
+
+/// Same as `index`, for a weighted ring, see `WeightedPiopProver`.
+///
+/// - `weights`: Weights of the ring members in the order of the keys.
+pub fn index_weighted<F: PrimeField, CS: PCS<F>, G: AffineRepr<BaseField = F>>(
+    pcs_params: &CS::Params,
+    piop_params: &PiopParams<G>,
+    keys: &[G],
+    weights: &[u64],
+) -> (WeightedProverKey<F, CS, G>, WeightedVerifierKey<F, CS>) {
+    let (prover_key, verifier_key) = index(pcs_params, piop_params, keys);
+    let weights = piop_params.ring_weights(weights);
+    let weights_committed = weights.commit(|poly| CS::commit(&prover_key.pcs_ck, poly).unwrap());
+    let verifier_key = WeightedVerifierKey {
+        verifier_key,
+        weights: weights_committed.clone(),
+    };
+    let prover_key = WeightedProverKey {
+        prover_key,
+        weights,
+        weights_committed,
+    };
+    (prover_key, verifier_key)
+}
+
+/// Same as `index`, for a ring of `K`-tuples of keys.
+pub fn index_tuples<F: PrimeField, CS: PCS<F>, G: AffineRepr<BaseField = F>, const K: usize>(
+    pcs_params: &CS::Params,
+    params: &TupleParams<G, K>,
+    keys: &[[G; K]],
+) -> (ProverKey<F, CS, G, K>, VerifierKey<F, CS, K>) {
+    let fixed_columns = params.fixed_columns(keys);
+    index_columns(pcs_params, fixed_columns)
+}
+
+fn index_columns<F: PrimeField, CS: PCS<F>, G: AffineRepr<BaseField = F>, const K: usize>(
+    pcs_params: &CS::Params,
+    fixed_columns: FixedColumns<F, G, K>,
+) -> (ProverKey<F, CS, G, K>, VerifierKey<F, CS, K>) {
     let pcs_ck = pcs_params.ck();
     let pcs_raw_vk = pcs_params.raw_vk();
-    let fixed_columns = piop_params.fixed_columns(&keys);
     let fixed_columns_committed = fixed_columns.commit::<CS>(&pcs_ck);
     let verifier_key = VerifierKey {
         pcs_raw_vk: pcs_raw_vk.clone(),
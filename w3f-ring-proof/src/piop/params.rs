@@ -1,14 +1,20 @@
 use ark_ec::{AdditiveGroup, AffineRepr, CurveGroup};
 use ark_ff::{BigInteger, One, PrimeField, Zero};
-use ark_std::rand::Rng;
-use ark_std::{vec, vec::Vec};
+use ark_std::rand::{Rng, RngCore};
+use ark_std::{array, vec, vec::Vec};
 use w3f_plonk_common::domain::Domain;
 use w3f_plonk_common::gadgets::ec::AffineColumn;
+use w3f_plonk_common::gadgets::range_check::powers_of_2_column;
+use w3f_plonk_common::FieldColumn;
 
+use crate::piop::weighted::RingWeights;
 use crate::piop::FixedColumns;
 
 pub const ZK_ROWS: usize = 3;
 
+/// Bit length of the weights of the ring members, see `PiopParams::ring_weights`.
+pub const WEIGHT_BITLEN: usize = 64;
+
 /// Plonk Interactive Oracle Proofs (PIOP) parameters.
 #[derive(Clone)]
 pub struct PiopParams<G: AffineRepr<BaseField: PrimeField>> {
@@ -62,18 +68,34 @@ impl<G: AffineRepr<BaseField: PrimeField>> PiopParams<G> {
         let ring_selector = self.domain.public_column(ring_selector);
         let points = self.points_column(&keys);
         FixedColumns {
-            points,
+            points: [points],
+            ring_selector,
+        }
+    }
+
+    /// Same as `fixed_columns`, but the points columns are randomized in the zk rows,
+    /// so that up to `ZK_ROWS` openings of the columns reveal nothing about the keys. See the `hidden` module.
+    pub fn hidden_fixed_columns(&self, keys: &[G]) -> FixedColumns<G::BaseField, G> {
+        let ring_selector = self.keyset_part_selector();
+        let ring_selector = self.domain.public_column(ring_selector);
+        let points = AffineColumn::column(self.points(keys), &self.domain);
+        FixedColumns {
+            points: [points],
             ring_selector,
         }
     }
 
     pub fn points_column(&self, keys: &[G]) -> AffineColumn<G::BaseField, G> {
+        AffineColumn::public_column(self.points(keys), &self.domain)
+    }
+
+    fn points(&self, keys: &[G]) -> Vec<G> {
         assert!(keys.len() <= self.keyset_part_size);
         let padding_len = self.keyset_part_size - keys.len();
         let padding = vec![self.padding; padding_len];
         let points = [keys, &padding, &self.power_of_2_multiples_of_h()].concat();
         assert_eq!(points.len(), self.domain.capacity - 1);
-        AffineColumn::public_column(points, &self.domain)
+        points
     }
 
     pub fn power_of_2_multiples_of_h(&self) -> Vec<G> {
@@ -101,12 +123,124 @@ impl<G: AffineRepr<BaseField: PrimeField>> PiopParams<G> {
         .concat()
     }
 
+    /// Selector of a subset of the keyset part, e.g. of a range of slots, or of the slots of the members with a certain role.
+    /// Unlike the ring selector, is chosen per proof, see `RingProver::rerandomize_pk_in_subset`.
+    pub fn subset_selector(&self, slots: &[usize]) -> FieldColumn<G::BaseField> {
+        let mut selector = vec![G::BaseField::zero(); self.keyset_part_size + self.scalar_bitlen];
+        for &i in slots {
+            assert!(i < self.keyset_part_size);
+            selector[i] = G::BaseField::one();
+        }
+        self.domain.public_column(selector)
+    }
+
+    /// Public column of the weights of the ring members (e.g. stakes), in the order of the keys, padded with zeroes.
+    pub fn weights(&self, weights: &[u64]) -> FieldColumn<G::BaseField> {
+        assert!(weights.len() <= self.keyset_part_size);
+        let mut column: Vec<_> = weights.iter().map(|&w| G::BaseField::from(w)).collect();
+        column.resize(
+            self.keyset_part_size + self.scalar_bitlen,
+            G::BaseField::zero(),
+        );
+        self.domain.public_column(column)
+    }
+
+    /// Public column `1, 2, ..., 2^(WEIGHT_BITLEN-1), 0, ..., 0` used to range check the weights.
+    pub fn powers_of_2(&self) -> FieldColumn<G::BaseField> {
+        powers_of_2_column(WEIGHT_BITLEN, &self.domain)
+    }
+
+    /// Weight columns of a weighted ring, fixed with the keys, see `index_weighted`.
+    pub fn ring_weights(&self, weights: &[u64]) -> RingWeights<G::BaseField> {
+        RingWeights {
+            weights: self.weights(weights),
+            powers_of_2: self.powers_of_2(),
+        }
+    }
+
+    /// Digest identifying the parameters, that binds the artifacts computed under them, see `PackedProverKey`.
+    pub fn digest(&self) -> [u8; 32] {
+        let mut t = ark_transcript::Transcript::new_labeled(b"w3f-ring-proof-piop-params");
+        t.label(b"domain_size");
+        t.append(&(self.domain.domain_size() as u64));
+        t.label(b"zk_rows");
+        t.append(&(self.domain.zk_rows as u64));
+        t.label(b"scalar_bitlen");
+        t.append(&(self.scalar_bitlen as u64));
+        t.label(b"keyset_part_size");
+        t.append(&(self.keyset_part_size as u64));
+        t.label(b"points");
+        t.append(&[self.h, self.seed, self.padding]);
+        let mut digest = [0; 32];
+        t.challenge(b"digest").fill_bytes(&mut digest);
+        digest
+    }
+
     pub fn blind_pk(&self, pk_k: G, blinding: G::ScalarField) -> G {
         let blinded_pk = pk_k + self.h * blinding;
         blinded_pk.into_affine()
     }
 }
 
+/// PIOP parameters of a ring of `K`-tuples of keys, e.g. of a consensus key and a VRF key of a member.
+///
+/// Every tuple component gets its own points column and `CondAdd` accumulator, and all of them are driven by
+/// the same bits column, so that the keys re-randomized by a single proof come from the same ring row.
+/// The blinding factor is also shared, but every component is blinded with its own base `H_j`,
+/// so the blinded keys `pk_j + rH_j` of a tuple remain unlinkable to the tuple under DDH.
+///
+/// With `K = 1` it is the plain ring, with `hs = [piop_params.h]`.
+#[derive(Clone)]
+pub struct TupleParams<G: AffineRepr<BaseField: PrimeField>, const K: usize> {
+    pub piop_params: PiopParams<G>,
+    /// Blinding base points of the tuple components. Should be of an unknown discrete log.
+    pub hs: [G; K],
+}
+
+impl<G: AffineRepr<BaseField: PrimeField>, const K: usize> TupleParams<G, K> {
+    pub fn setup(piop_params: PiopParams<G>, hs: [G; K]) -> Self {
+        Self { piop_params, hs }
+    }
+
+    pub fn rand<R: Rng>(domain_size: usize, rng: &mut R) -> Self {
+        let piop_params = PiopParams::rand(domain_size, rng);
+        let hs = array::from_fn(|_| G::rand(rng));
+        Self::setup(piop_params, hs)
+    }
+
+    /// Parameters of the ring of the `j`-th components of the tuples.
+    pub fn component(&self, j: usize) -> PiopParams<G> {
+        PiopParams {
+            h: self.hs[j],
+            ..self.piop_params.clone()
+        }
+    }
+
+    pub fn fixed_columns(&self, keys: &[[G; K]]) -> FixedColumns<G::BaseField, G, K> {
+        let points = array::from_fn(|j| {
+            let keys: Vec<G> = keys.iter().map(|tuple| tuple[j]).collect();
+            self.component(j).points_column(&keys)
+        });
+        let ring_selector = self.piop_params.keyset_part_selector();
+        let ring_selector = self.piop_params.domain.public_column(ring_selector);
+        FixedColumns {
+            points,
+            ring_selector,
+        }
+    }
+
+    pub fn blind_pks(&self, pks: &[G; K], blinding: G::ScalarField) -> [G; K] {
+        array::from_fn(|j| (pks[j] + self.hs[j] * blinding).into_affine())
+    }
+}
+
+impl<G: AffineRepr<BaseField: PrimeField>> From<PiopParams<G>> for TupleParams<G, 1> {
+    fn from(piop_params: PiopParams<G>) -> Self {
+        let hs = [piop_params.h];
+        Self::setup(piop_params, hs)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use ark_ed_on_bls12_381_bandersnatch::{EdwardsAffine, Fr};
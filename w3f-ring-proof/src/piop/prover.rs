@@ -6,7 +6,7 @@ use ark_poly::univariate::DensePolynomial;
 use ark_poly::Evaluations;
 use ark_std::marker::PhantomData;
 
-use ark_std::{vec, vec::Vec};
+use ark_std::{array, vec, vec::Vec};
 use w3f_pcs::pcs::Commitment;
 
 use crate::piop::params::PiopParams;
@@ -24,10 +24,13 @@ use w3f_plonk_common::FieldColumn;
 
 // The 'table': columns representing the execution trace of the computation
 // and the constraints -- polynomials that vanish on every 2 consecutive rows.
-pub struct PiopProver<F: PrimeField, G: AffineRepr<BaseField = F>> {
+//
+// For a ring of `K`-tuples, every tuple component gets its own points column and `CondAdd` accumulator,
+// all driven by the same bits column, see `TupleParams`.
+pub struct PiopProver<F: PrimeField, G: AffineRepr<BaseField = F>, const K: usize = 1> {
     domain: Domain<F>,
     /// Advice (public input) columns
-    points: AffineColumn<F, G>,
+    points: [AffineColumn<F, G>; K],
     ring_selector: FieldColumn<F>,
     // Private input column.
     bits: BitColumn<F>,
@@ -35,15 +38,14 @@ pub struct PiopProver<F: PrimeField, G: AffineRepr<BaseField = F>> {
     booleanity: Booleanity<F>,
     inner_prod: InnerProd<F>,
     inner_prod_acc: FixedCells<F>,
-    cond_add: CondAdd<F, G>,
-    cond_add_acc_x: FixedCells<F>,
-    cond_add_acc_y: FixedCells<F>,
+    cond_add: [CondAdd<F, G>; K],
+    cond_add_acc: [[FixedCells<F>; 2]; K],
 }
 
-impl<F: PrimeField, G: AffineRepr<BaseField = F>> PiopProver<F, G> {
+impl<F: PrimeField, G: AffineRepr<BaseField = F>, const K: usize> PiopProver<F, G, K> {
     pub fn build(
         params: &PiopParams<G>,
-        fixed_columns: FixedColumns<F, G>,
+        fixed_columns: FixedColumns<F, G, K>,
         prover_index_in_keys: usize,
         secret: G::ScalarField,
     ) -> Self {
@@ -51,32 +53,47 @@ impl<F: PrimeField, G: AffineRepr<BaseField = F>> PiopProver<F, G> {
         let FixedColumns {
             points,
             ring_selector,
+            ..
         } = fixed_columns;
         let bits = Self::bits_column(&params, prover_index_in_keys, secret);
         let booleanity = Booleanity::init(bits.clone());
         let inner_prod = InnerProd::init(ring_selector.clone(), bits.col.clone(), &domain);
         let inner_prod_acc = FixedCells::init(inner_prod.acc.clone(), &domain, F::zero(), F::one());
-        let cond_add = CondAdd::init(bits.clone(), points.clone(), params.seed, &domain);
+        let cond_add = array::from_fn(|j| {
+            CondAdd::init(bits.clone(), points[j].clone(), params.seed, &domain)
+        });
         let (seed_x, seed_y) = params.seed.xy().unwrap();
-        let (result_x, result_y) = cond_add.seed_plus_sum().xy().unwrap();
-        let cond_add_acc_x = FixedCells::init(cond_add.acc.xs.clone(), &domain, seed_x, result_x);
-        let cond_add_acc_y = FixedCells::init(cond_add.acc.ys.clone(), &domain, seed_y, result_y);
+        let cond_add_acc = array::from_fn(|j| {
+            let cond_add: &CondAdd<F, G> = &cond_add[j];
+            let (result_x, result_y) = cond_add.seed_plus_sum().xy().unwrap();
+            [
+                FixedCells::init(cond_add.acc.xs.clone(), &domain, seed_x, result_x),
+                FixedCells::init(cond_add.acc.ys.clone(), &domain, seed_y, result_y),
+            ]
+        });
         Self {
             domain,
             points,
             ring_selector,
             bits,
             inner_prod_acc,
-            cond_add_acc_x,
-            cond_add_acc_y,
+            cond_add_acc,
             booleanity,
             inner_prod,
             cond_add,
         }
     }
 
+    pub(crate) fn bits(&self) -> &BitColumn<F> {
+        &self.bits
+    }
+
+    pub(crate) fn ring_selector(&self) -> &FieldColumn<F> {
+        &self.ring_selector
+    }
+
     // TODO: move to params?
-    fn bits_column(
+    pub(crate) fn bits_column(
         params: &PiopParams<G>,
         index_in_keys: usize,
         secret: G::ScalarField,
@@ -92,12 +109,14 @@ impl<F: PrimeField, G: AffineRepr<BaseField = F>> PiopProver<F, G> {
     fn _committed_columns<C: Commitment<F>, Fun: Fn(&DensePolynomial<F>) -> C>(
         &self,
         commit: Fun,
-    ) -> RingCommitments<F, C> {
+    ) -> RingCommitments<F, C, K> {
         let bits = commit(self.bits.as_poly());
-        let cond_add_acc = [
-            commit(self.cond_add.acc.xs.as_poly()),
-            commit(self.cond_add.acc.ys.as_poly()),
-        ];
+        let cond_add_acc = array::from_fn(|j| {
+            [
+                commit(self.cond_add[j].acc.xs.as_poly()),
+                commit(self.cond_add[j].acc.ys.as_poly()),
+            ]
+        });
         let inn_prod_acc = commit(self.inner_prod.acc.as_poly());
         RingCommitments {
             bits,
@@ -110,26 +129,37 @@ impl<F: PrimeField, G: AffineRepr<BaseField = F>> PiopProver<F, G> {
     // Should return polynomials in the consistent with
     // Self::Evaluations::to_vec() and Self::Commitments::to_vec().
     fn _columns(&self) -> Vec<DensePolynomial<F>> {
-        vec![
-            self.points.xs.as_poly().clone(),
-            self.points.ys.as_poly().clone(),
-            self.ring_selector.as_poly().clone(),
-            self.bits.as_poly().clone(),
-            self.inner_prod.acc.as_poly().clone(),
-            self.cond_add.acc.xs.as_poly().clone(),
-            self.cond_add.acc.ys.as_poly().clone(),
-        ]
+        let mut columns = Vec::with_capacity(4 * K + 3);
+        for points in &self.points {
+            columns.push(points.xs.as_poly().clone());
+            columns.push(points.ys.as_poly().clone());
+        }
+        columns.push(self.ring_selector.as_poly().clone());
+        columns.push(self.bits.as_poly().clone());
+        columns.push(self.inner_prod.acc.as_poly().clone());
+        for cond_add in &self.cond_add {
+            columns.push(cond_add.acc.xs.as_poly().clone());
+            columns.push(cond_add.acc.ys.as_poly().clone());
+        }
+        columns
     }
 
-    fn _columns_evaluated(&self, zeta: &F) -> RingEvaluations<F> {
-        let points = [self.points.xs.evaluate(zeta), self.points.ys.evaluate(zeta)];
+    fn _columns_evaluated(&self, zeta: &F) -> RingEvaluations<F, K> {
+        let points = array::from_fn(|j| {
+            [
+                self.points[j].xs.evaluate(zeta),
+                self.points[j].ys.evaluate(zeta),
+            ]
+        });
         let ring_selector = self.ring_selector.evaluate(zeta);
         let bits = self.bits.evaluate(zeta);
         let inn_prod_acc = self.inner_prod.acc.evaluate(zeta);
-        let cond_add_acc = [
-            self.cond_add.acc.xs.evaluate(zeta),
-            self.cond_add.acc.ys.evaluate(zeta),
-        ];
+        let cond_add_acc = array::from_fn(|j| {
+            [
+                self.cond_add[j].acc.xs.evaluate(zeta),
+                self.cond_add[j].acc.ys.evaluate(zeta),
+            ]
+        });
         RingEvaluations {
             points,
             ring_selector,
@@ -138,20 +168,50 @@ impl<F: PrimeField, G: AffineRepr<BaseField = F>> PiopProver<F, G> {
             cond_add_acc,
         }
     }
+
+    // The `CondAdd` constraints of all the components go after the inner product constraint,
+    // followed by the booleanity constraint, and the boundary constraints.
+    fn _constraints(&self) -> Vec<Evaluations<F>> {
+        let mut constraints = self.inner_prod.constraints();
+        for cond_add in &self.cond_add {
+            constraints.extend(cond_add.constraints());
+        }
+        constraints.extend(self.booleanity.constraints());
+        for [acc_x, acc_y] in &self.cond_add_acc {
+            constraints.extend(acc_x.constraints());
+            constraints.extend(acc_y.constraints());
+        }
+        constraints.extend(self.inner_prod_acc.constraints());
+        constraints
+    }
+
+    fn _constraints_lin(&self, zeta: &F) -> Vec<DensePolynomial<F>> {
+        let mut constraints = self.inner_prod.constraints_linearized(zeta);
+        for cond_add in &self.cond_add {
+            constraints.extend(cond_add.constraints_linearized(zeta));
+        }
+        constraints.extend(self.booleanity.constraints_linearized(zeta));
+        for [acc_x, acc_y] in &self.cond_add_acc {
+            constraints.extend(acc_x.constraints_linearized(zeta));
+            constraints.extend(acc_y.constraints_linearized(zeta));
+        }
+        constraints.extend(self.inner_prod_acc.constraints_linearized(zeta));
+        constraints
+    }
 }
 
-impl<F, C, Curve> ProverPiop<F, C> for PiopProver<F, TeAffine<Curve>>
+impl<F, C, Curve, const K: usize> ProverPiop<F, C> for PiopProver<F, TeAffine<Curve>, K>
 where
     F: PrimeField,
     C: Commitment<F>,
     Curve: TECurveConfig<BaseField = F>,
 {
-    const N_COLUMNS: usize = 7;
-    const N_CONSTRAINTS: usize = 7;
+    const N_COLUMNS: usize = 4 * K + 3;
+    const N_CONSTRAINTS: usize = 4 * K + 3;
 
-    type Commitments = RingCommitments<F, C>;
-    type Evaluations = RingEvaluations<F>;
-    type Instance = TeAffine<Curve>;
+    type Commitments = RingCommitments<F, C, K>;
+    type Evaluations = RingEvaluations<F, K>;
+    type Instance = [TeAffine<Curve>; K];
 
     fn committed_columns<Fun: Fn(&DensePolynomial<F>) -> C>(
         &self,
@@ -171,27 +231,11 @@ where
     }
 
     fn constraints(&self) -> Vec<Evaluations<F>> {
-        vec![
-            self.inner_prod.constraints(),
-            self.cond_add.constraints(),
-            self.booleanity.constraints(),
-            self.cond_add_acc_x.constraints(),
-            self.cond_add_acc_y.constraints(),
-            self.inner_prod_acc.constraints(),
-        ]
-        .concat()
+        self._constraints()
     }
 
     fn constraints_lin(&self, zeta: &F) -> Vec<DensePolynomial<F>> {
-        vec![
-            self.inner_prod.constraints_linearized(zeta),
-            self.cond_add.constraints_linearized(zeta),
-            self.booleanity.constraints_linearized(zeta),
-            self.cond_add_acc_x.constraints_linearized(zeta),
-            self.cond_add_acc_y.constraints_linearized(zeta),
-            self.inner_prod_acc.constraints_linearized(zeta),
-        ]
-        .concat()
+        self._constraints_lin(zeta)
     }
 
     fn domain(&self) -> &Domain<F> {
@@ -199,21 +243,21 @@ where
     }
 
     fn result(&self) -> Self::Instance {
-        self.cond_add.result()
+        array::from_fn(|j| self.cond_add[j].result())
     }
 }
 
-impl<F, C, Curve> ProverPiop<F, C> for PiopProver<F, SwAffine<Curve>>
+impl<F, C, Curve, const K: usize> ProverPiop<F, C> for PiopProver<F, SwAffine<Curve>, K>
 where
     F: PrimeField,
     C: Commitment<F>,
     Curve: SWCurveConfig<BaseField = F>,
 {
-    const N_COLUMNS: usize = 7;
-    const N_CONSTRAINTS: usize = 7;
-    type Commitments = RingCommitments<F, C>;
-    type Evaluations = RingEvaluations<F>;
-    type Instance = SwAffine<Curve>;
+    const N_COLUMNS: usize = 4 * K + 3;
+    const N_CONSTRAINTS: usize = 4 * K + 3;
+    type Commitments = RingCommitments<F, C, K>;
+    type Evaluations = RingEvaluations<F, K>;
+    type Instance = [SwAffine<Curve>; K];
 
     fn committed_columns<Fun: Fn(&DensePolynomial<F>) -> C>(
         &self,
@@ -233,27 +277,11 @@ where
     }
 
     fn constraints(&self) -> Vec<Evaluations<F>> {
-        vec![
-            self.inner_prod.constraints(),
-            self.cond_add.constraints(),
-            self.booleanity.constraints(),
-            self.cond_add_acc_x.constraints(),
-            self.cond_add_acc_y.constraints(),
-            self.inner_prod_acc.constraints(),
-        ]
-        .concat()
+        self._constraints()
     }
 
     fn constraints_lin(&self, zeta: &F) -> Vec<DensePolynomial<F>> {
-        vec![
-            self.inner_prod.constraints_linearized(zeta),
-            self.cond_add.constraints_linearized(zeta),
-            self.booleanity.constraints_linearized(zeta),
-            self.cond_add_acc_x.constraints_linearized(zeta),
-            self.cond_add_acc_y.constraints_linearized(zeta),
-            self.inner_prod_acc.constraints_linearized(zeta),
-        ]
-        .concat()
+        self._constraints_lin(zeta)
     }
 
     fn domain(&self) -> &Domain<F> {
@@ -261,19 +289,20 @@ where
     }
 
     fn result(&self) -> Self::Instance {
-        self.cond_add.result()
+        array::from_fn(|j| self.cond_add[j].result())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::index;
+    use crate::piop::params::TupleParams;
     use crate::tests::setup;
+    use crate::{index, index_tuples};
     use ark_ed_on_bls12_381_bandersnatch::{EdwardsAffine, Fq, Fr};
     use ark_std::{test_rng, UniformRand};
     use w3f_pcs::pcs::id::WrappedPolynomial;
-    use w3f_pcs::pcs::IdentityCommitment;
+    use w3f_pcs::pcs::{IdentityCommitment, PCS};
     use w3f_plonk_common::test_helpers::random_vec;
 
     #[test]
@@ -292,4 +321,26 @@ mod tests {
             PiopProver::build(&piop_params, fixed_columns, 1, Fr::rand(rng));
         assert!(ProverPiop::<Fq, WrappedPolynomial<Fq>>::constraints_satisfied(&piop));
     }
+
+    #[test]
+    fn test_tuple_constraints() {
+        let rng = &mut test_rng();
+
+        let params = TupleParams::<EdwardsAffine, 2>::rand(1 << 9, rng);
+        let keys: Vec<[EdwardsAffine; 2]> = random_vec::<EdwardsAffine, _>(20, rng)
+            .chunks(2)
+            .map(|tuple| [tuple[0], tuple[1]])
+            .collect();
+        let pcs_params = IdentityCommitment::setup(0, rng);
+        let (prover_key, _) =
+            index_tuples::<_, IdentityCommitment, _, 2>(&pcs_params, &params, &keys);
+        let r = Fr::rand(rng);
+        let piop: PiopProver<Fq, EdwardsAffine, 2> =
+            PiopProver::build(&params.piop_params, prover_key.fixed_columns, 3, r);
+        assert!(ProverPiop::<Fq, WrappedPolynomial<Fq>>::constraints_satisfied(&piop));
+        assert_eq!(
+            ProverPiop::<Fq, WrappedPolynomial<Fq>>::result(&piop),
+            params.blind_pks(&keys[3], r)
+        );
+    }
 }
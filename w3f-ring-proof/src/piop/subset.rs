@@ -0,0 +1,224 @@
+//! Variant of the ring PIOP proving membership in a public subset of the ring,
+//! e.g. in a range of slots, or in the slots of the members with a certain role.
+//!
+//! The subset is given by a selector column, the commitment to which the verifier knows.
+//! On top of the ring constraints, the bit highlighting the prover's key is constrained
+//! to be set in a row highlighted by the subset selector.
+
+use ark_ec::AffineRepr;
+use ark_ff::PrimeField;
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::Evaluations;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{vec, vec::Vec};
+use w3f_pcs::pcs::Commitment;
+
+use w3f_plonk_common::domain::{Domain, EvaluatedDomain};
+use w3f_plonk_common::gadgets::inclusion::{Inclusion, InclusionValues};
+use w3f_plonk_common::gadgets::VerifierGadget;
+use w3f_plonk_common::piop::{ProverPiop, VerifierPiop};
+use w3f_plonk_common::{Column, ColumnsEvaluated, FieldColumn};
+
+use crate::piop::params::PiopParams;
+use crate::piop::{
+    FixedColumns, FixedColumnsCommitted, PiopProver, PiopVerifier, RingCommitments, RingEvaluations,
+};
+
+// Index of the subset selector in the list of columns, right after the precommitted ring columns.
+const SUBSET_SELECTOR_INDEX: usize = 3;
+
+/// Selector column of a subset of the ring slots, together with the commitment to it.
+/// See `PiopParams::subset_selector` and `Ring::subset_selector`.
+#[derive(Clone)]
+pub struct SubsetSelector<F: PrimeField, C: Commitment<F>> {
+    pub column: FieldColumn<F>,
+    pub commitment: C,
+}
+
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct SubsetRingEvaluations<F: PrimeField> {
+    pub(crate) ring: RingEvaluations<F>,
+    pub(crate) subset_selector: F,
+}
+
+impl<F: PrimeField> ColumnsEvaluated<F> for SubsetRingEvaluations<F> {
+    fn to_vec(self) -> Vec<F> {
+        let mut evals = self.ring.to_vec();
+        evals.insert(SUBSET_SELECTOR_INDEX, self.subset_selector);
+        evals
+    }
+}
+
+pub struct SubsetPiopProver<F: PrimeField, C: Commitment<F>, G: AffineRepr<BaseField = F>> {
+    piop: PiopProver<F, G>,
+    subset_selector: SubsetSelector<F, C>,
+    inclusion: Inclusion<F>,
+}
+
+impl<F: PrimeField, C: Commitment<F>, G: AffineRepr<BaseField = F>> SubsetPiopProver<F, C, G> {
+    pub fn build(
+        params: &PiopParams<G>,
+        fixed_columns: FixedColumns<F, G>,
+        subset_selector: SubsetSelector<F, C>,
+        prover_index_in_keys: usize,
+        secret: G::ScalarField,
+    ) -> Self {
+        let piop = PiopProver::build(params, fixed_columns, prover_index_in_keys, secret);
+        let inclusion = Inclusion::init(
+            piop.bits().clone(),
+            piop.ring_selector().clone(),
+            subset_selector.column.clone(),
+        );
+        Self {
+            piop,
+            subset_selector,
+            inclusion,
+        }
+    }
+}
+
+impl<F, C, G> ProverPiop<F, C> for SubsetPiopProver<F, C, G>
+where
+    F: PrimeField,
+    C: Commitment<F>,
+    G: AffineRepr<BaseField = F>,
+    PiopProver<F, G>: ProverPiop<
+        F,
+        C,
+        Commitments = RingCommitments<F, C>,
+        Evaluations = RingEvaluations<F>,
+        Instance = [G; 1],
+    >,
+{
+    const N_COLUMNS: usize = <PiopProver<F, G> as ProverPiop<F, C>>::N_COLUMNS + 1;
+    const N_CONSTRAINTS: usize = <PiopProver<F, G> as ProverPiop<F, C>>::N_CONSTRAINTS + 1;
+
+    type Commitments = RingCommitments<F, C>;
+    type Evaluations = SubsetRingEvaluations<F>;
+    // The subset selector commitment is a part of the instance, so that it gets into the transcript.
+    type Instance = (G, C);
+
+    fn committed_columns<Fun: Fn(&DensePolynomial<F>) -> C>(
+        &self,
+        commit: Fun,
+    ) -> Self::Commitments {
+        self.piop.committed_columns(commit)
+    }
+
+    fn columns(&self) -> Vec<DensePolynomial<F>> {
+        let mut columns = ProverPiop::<F, C>::columns(&self.piop);
+        columns.insert(
+            SUBSET_SELECTOR_INDEX,
+            self.subset_selector.column.as_poly().clone(),
+        );
+        columns
+    }
+
+    fn columns_evaluated(&self, zeta: &F) -> Self::Evaluations {
+        SubsetRingEvaluations {
+            ring: ProverPiop::<F, C>::columns_evaluated(&self.piop, zeta),
+            subset_selector: self.subset_selector.column.evaluate(zeta),
+        }
+    }
+
+    fn constraints(&self) -> Vec<Evaluations<F>> {
+        vec![
+            ProverPiop::<F, C>::constraints(&self.piop),
+            self.inclusion.constraints(),
+        ]
+        .concat()
+    }
+
+    fn constraints_lin(&self, zeta: &F) -> Vec<DensePolynomial<F>> {
+        vec![
+            ProverPiop::<F, C>::constraints_lin(&self.piop, zeta),
+            self.inclusion.constraints_linearized(zeta),
+        ]
+        .concat()
+    }
+
+    fn domain(&self) -> &Domain<F> {
+        ProverPiop::<F, C>::domain(&self.piop)
+    }
+
+    fn result(&self) -> Self::Instance {
+        (
+            ProverPiop::<F, C>::result(&self.piop)[0],
+            self.subset_selector.commitment.clone(),
+        )
+    }
+}
+
+pub struct SubsetPiopVerifier<F: PrimeField, C: Commitment<F>, P: AffineRepr<BaseField = F>> {
+    piop: PiopVerifier<F, C, P>,
+    subset_selector: C,
+    inclusion: InclusionValues<F>,
+}
+
+impl<F: PrimeField, C: Commitment<F>, P: AffineRepr<BaseField = F>> SubsetPiopVerifier<F, C, P> {
+    pub fn init(
+        domain_evals: EvaluatedDomain<F>,
+        fixed_columns_committed: FixedColumnsCommitted<F, C>,
+        subset_selector: C,
+        witness_columns_committed: RingCommitments<F, C>,
+        all_columns_evaluated: SubsetRingEvaluations<F>,
+        init: (F, F),
+        result: (F, F),
+    ) -> Self {
+        let inclusion = InclusionValues {
+            bits: all_columns_evaluated.ring.bits,
+            superset: all_columns_evaluated.ring.ring_selector,
+            subset: all_columns_evaluated.subset_selector,
+        };
+        let piop = PiopVerifier::init(
+            domain_evals,
+            fixed_columns_committed,
+            witness_columns_committed,
+            all_columns_evaluated.ring,
+            init,
+            [result],
+        );
+        Self {
+            piop,
+            subset_selector,
+            inclusion,
+        }
+    }
+}
+
+impl<F, C, P> VerifierPiop<F, C> for SubsetPiopVerifier<F, C, P>
+where
+    F: PrimeField,
+    C: Commitment<F>,
+    P: AffineRepr<BaseField = F>,
+    PiopVerifier<F, C, P>: VerifierPiop<F, C, Instance = [P; 1]>,
+{
+    const N_COLUMNS: usize = <PiopVerifier<F, C, P> as VerifierPiop<F, C>>::N_COLUMNS + 1;
+    const N_CONSTRAINTS: usize = <PiopVerifier<F, C, P> as VerifierPiop<F, C>>::N_CONSTRAINTS + 1;
+    type Instance = (P, C);
+
+    fn precommitted_columns(&self) -> Vec<C> {
+        let mut columns = self.piop.precommitted_columns();
+        columns.push(self.subset_selector.clone());
+        columns
+    }
+
+    fn evaluate_constraints_main(&self) -> Vec<F> {
+        vec![
+            self.piop.evaluate_constraints_main(),
+            self.inclusion.evaluate_constraints_main(),
+        ]
+        .concat()
+    }
+
+    fn lin_poly_commitment(&self, agg_coeffs: &[F]) -> (Vec<F>, Vec<C>) {
+        assert_eq!(agg_coeffs.len(), Self::N_CONSTRAINTS);
+        // The inclusion constraint doesn't contribute to the linearization polynomial.
+        self.piop
+            .lin_poly_commitment(&agg_coeffs[..agg_coeffs.len() - 1])
+    }
+
+    fn domain_evaluated(&self) -> &EvaluatedDomain<F> {
+        self.piop.domain_evaluated()
+    }
+}
@@ -3,7 +3,7 @@ use ark_ec::twisted_edwards::{Affine as TeAffine, TECurveConfig};
 use ark_ec::AffineRepr;
 use ark_ff::PrimeField;
 use ark_std::marker::PhantomData;
-use ark_std::{vec, vec::Vec};
+use ark_std::{array, vec, vec::Vec};
 use w3f_pcs::pcs::Commitment;
 
 use w3f_plonk_common::domain::EvaluatedDomain;
@@ -18,41 +18,47 @@ use w3f_plonk_common::piop::VerifierPiop;
 use crate::piop::{FixedColumnsCommitted, PiopProver, RingCommitments};
 use crate::RingEvaluations;
 
-pub struct PiopVerifier<F: PrimeField, C: Commitment<F>, P: AffineRepr<BaseField = F>> {
+pub struct PiopVerifier<
+    F: PrimeField,
+    C: Commitment<F>,
+    P: AffineRepr<BaseField = F>,
+    const K: usize = 1,
+> {
     domain_evals: EvaluatedDomain<F>,
-    fixed_columns_committed: FixedColumnsCommitted<F, C>,
-    witness_columns_committed: RingCommitments<F, C>,
+    fixed_columns_committed: FixedColumnsCommitted<F, C, K>,
+    witness_columns_committed: RingCommitments<F, C, K>,
     // Gadget verifiers:
     booleanity: BooleanityValues<F>,
     inner_prod: InnerProdValues<F>,
     inner_prod_acc: FixedCellsValues<F>,
-    cond_add: CondAddValues<F, P>,
-    cond_add_acc_x: FixedCellsValues<F>,
-    cond_add_acc_y: FixedCellsValues<F>,
+    cond_add: [CondAddValues<F, P>; K],
+    cond_add_acc: [[FixedCellsValues<F>; 2]; K],
 }
 
-impl<F: PrimeField, C: Commitment<F>, P: AffineRepr<BaseField = F>> PiopVerifier<F, C, P> {
+impl<F: PrimeField, C: Commitment<F>, P: AffineRepr<BaseField = F>, const K: usize>
+    PiopVerifier<F, C, P, K>
+{
     pub fn init(
         domain_evals: EvaluatedDomain<F>,
-        fixed_columns_committed: FixedColumnsCommitted<F, C>,
-        witness_columns_committed: RingCommitments<F, C>,
-        all_columns_evaluated: RingEvaluations<F>,
+        fixed_columns_committed: FixedColumnsCommitted<F, C, K>,
+        witness_columns_committed: RingCommitments<F, C, K>,
+        all_columns_evaluated: RingEvaluations<F, K>,
         init: (F, F),
-        result: (F, F),
+        results: [(F, F); K],
     ) -> Self {
-        let cond_add = CondAddValues {
+        let cond_add = array::from_fn(|j| CondAddValues {
             bitmask: all_columns_evaluated.bits,
             points: (
-                all_columns_evaluated.points[0],
-                all_columns_evaluated.points[1],
+                all_columns_evaluated.points[j][0],
+                all_columns_evaluated.points[j][1],
             ),
             not_last: domain_evals.not_last_row,
             acc: (
-                all_columns_evaluated.cond_add_acc[0],
-                all_columns_evaluated.cond_add_acc[1],
+                all_columns_evaluated.cond_add_acc[j][0],
+                all_columns_evaluated.cond_add_acc[j][1],
             ),
             _phantom: PhantomData,
-        };
+        });
 
         let inner_prod = InnerProdValues {
             a: all_columns_evaluated.ring_selector,
@@ -65,21 +71,24 @@ impl<F: PrimeField, C: Commitment<F>, P: AffineRepr<BaseField = F>> PiopVerifier
             bits: all_columns_evaluated.bits,
         };
 
-        let cond_add_acc_x = FixedCellsValues {
-            col: all_columns_evaluated.cond_add_acc[0],
-            col_first: init.0,
-            col_last: result.0,
-            l_first: domain_evals.l_first,
-            l_last: domain_evals.l_last,
-        };
-
-        let cond_add_acc_y = FixedCellsValues {
-            col: all_columns_evaluated.cond_add_acc[1],
-            col_first: init.1,
-            col_last: result.1,
-            l_first: domain_evals.l_first,
-            l_last: domain_evals.l_last,
-        };
+        let cond_add_acc = array::from_fn(|j| {
+            [
+                FixedCellsValues {
+                    col: all_columns_evaluated.cond_add_acc[j][0],
+                    col_first: init.0,
+                    col_last: results[j].0,
+                    l_first: domain_evals.l_first,
+                    l_last: domain_evals.l_last,
+                },
+                FixedCellsValues {
+                    col: all_columns_evaluated.cond_add_acc[j][1],
+                    col_first: init.1,
+                    col_last: results[j].1,
+                    l_first: domain_evals.l_first,
+                    l_last: domain_evals.l_last,
+                },
+            ]
+        });
 
         let inner_prod_acc = FixedCellsValues {
             col: all_columns_evaluated.inn_prod_acc,
@@ -96,56 +105,72 @@ impl<F: PrimeField, C: Commitment<F>, P: AffineRepr<BaseField = F>> PiopVerifier
             inner_prod,
             cond_add,
             booleanity,
-            cond_add_acc_x,
-            cond_add_acc_y,
+            cond_add_acc,
             inner_prod_acc,
         }
     }
+
+    // Same order as in `PiopProver::constraints`.
+    fn _evaluate_constraints_main(&self) -> Vec<F> {
+        let mut constraints = self.inner_prod.evaluate_constraints_main();
+        for cond_add in &self.cond_add {
+            constraints.extend(cond_add.evaluate_constraints_main());
+        }
+        constraints.extend(self.booleanity.evaluate_constraints_main());
+        for [acc_x, acc_y] in &self.cond_add_acc {
+            constraints.extend(acc_x.evaluate_constraints_main());
+            constraints.extend(acc_y.evaluate_constraints_main());
+        }
+        constraints.extend(self.inner_prod_acc.evaluate_constraints_main());
+        constraints
+    }
+
+    fn _lin_poly_commitment(&self, agg_coeffs: &[F]) -> (Vec<F>, Vec<C>) {
+        let inner_prod_acc = self.witness_columns_committed.inn_prod_acc.clone();
+        let inner_prod_coeff = agg_coeffs[0] * self.inner_prod.not_last;
+
+        let mut coeffs = vec![inner_prod_coeff];
+        let mut commitments = vec![inner_prod_acc];
+        // The `CondAdd` constraints of the `j`-th component go at `1 + 2j` and `2 + 2j`.
+        for (j, cond_add) in self.cond_add.iter().enumerate() {
+            let [cond_add_acc_x, cond_add_acc_y] =
+                self.witness_columns_committed.cond_add_acc[j].clone();
+            let (c_acc_x, c_acc_y) = cond_add.acc_coeffs_1();
+            let mut cond_add_x_coeff = agg_coeffs[1 + 2 * j] * c_acc_x;
+            let mut cond_add_y_coeff = agg_coeffs[1 + 2 * j] * c_acc_y;
+            let (c_acc_x, c_acc_y) = cond_add.acc_coeffs_2();
+            cond_add_x_coeff += agg_coeffs[2 + 2 * j] * c_acc_x;
+            cond_add_y_coeff += agg_coeffs[2 + 2 * j] * c_acc_y;
+            coeffs.extend([cond_add_x_coeff, cond_add_y_coeff]);
+            commitments.extend([cond_add_acc_x, cond_add_acc_y]);
+        }
+
+        (coeffs, commitments)
+    }
 }
 
-impl<F: PrimeField, C: Commitment<F>, Jubjub: TECurveConfig<BaseField = F>> VerifierPiop<F, C>
-    for PiopVerifier<F, C, TeAffine<Jubjub>>
+impl<F, C, Jubjub, const K: usize> VerifierPiop<F, C> for PiopVerifier<F, C, TeAffine<Jubjub>, K>
+where
+    F: PrimeField,
+    C: Commitment<F>,
+    Jubjub: TECurveConfig<BaseField = F>,
 {
-    const N_COLUMNS: usize = <PiopProver<F, TeAffine<Jubjub>> as ProverPiop<F, C>>::N_COLUMNS;
+    const N_COLUMNS: usize = <PiopProver<F, TeAffine<Jubjub>, K> as ProverPiop<F, C>>::N_COLUMNS;
     const N_CONSTRAINTS: usize =
-        <PiopProver<F, TeAffine<Jubjub>> as ProverPiop<F, C>>::N_CONSTRAINTS;
-    type Instance = <PiopProver<F, TeAffine<Jubjub>> as ProverPiop<F, C>>::Instance;
+        <PiopProver<F, TeAffine<Jubjub>, K> as ProverPiop<F, C>>::N_CONSTRAINTS;
+    type Instance = <PiopProver<F, TeAffine<Jubjub>, K> as ProverPiop<F, C>>::Instance;
 
     fn precommitted_columns(&self) -> Vec<C> {
         self.fixed_columns_committed.as_vec()
     }
 
     fn evaluate_constraints_main(&self) -> Vec<F> {
-        vec![
-            self.inner_prod.evaluate_constraints_main(),
-            self.cond_add.evaluate_constraints_main(),
-            self.booleanity.evaluate_constraints_main(),
-            self.cond_add_acc_x.evaluate_constraints_main(),
-            self.cond_add_acc_y.evaluate_constraints_main(),
-            self.inner_prod_acc.evaluate_constraints_main(),
-        ]
-        .concat()
+        self._evaluate_constraints_main()
     }
 
     fn lin_poly_commitment(&self, agg_coeffs: &[F]) -> (Vec<F>, Vec<C>) {
         assert_eq!(agg_coeffs.len(), Self::N_CONSTRAINTS);
-
-        let inner_prod_acc = self.witness_columns_committed.inn_prod_acc.clone();
-        let inner_prod_coeff = agg_coeffs[0] * self.inner_prod.not_last;
-
-        let cond_add_acc_x = self.witness_columns_committed.cond_add_acc[0].clone();
-        let cond_add_acc_y = self.witness_columns_committed.cond_add_acc[1].clone();
-        let (c_acc_x, c_acc_y) = self.cond_add.acc_coeffs_1();
-        let mut cond_add_x_coeff = agg_coeffs[1] * c_acc_x;
-        let mut cond_add_y_coeff = agg_coeffs[1] * c_acc_y;
-        let (c_acc_x, c_acc_y) = self.cond_add.acc_coeffs_2();
-        cond_add_x_coeff += agg_coeffs[2] * c_acc_x;
-        cond_add_y_coeff += agg_coeffs[2] * c_acc_y;
-
-        (
-            vec![inner_prod_coeff, cond_add_x_coeff, cond_add_y_coeff],
-            vec![inner_prod_acc.clone(), cond_add_acc_x, cond_add_acc_y],
-        )
+        self._lin_poly_commitment(agg_coeffs)
     }
 
     fn domain_evaluated(&self) -> &EvaluatedDomain<F> {
@@ -153,49 +178,28 @@ impl<F: PrimeField, C: Commitment<F>, Jubjub: TECurveConfig<BaseField = F>> Veri
     }
 }
 
-impl<F: PrimeField, C: Commitment<F>, Jubjub: SWCurveConfig<BaseField = F>> VerifierPiop<F, C>
-    for PiopVerifier<F, C, SwAffine<Jubjub>>
+impl<F, C, Jubjub, const K: usize> VerifierPiop<F, C> for PiopVerifier<F, C, SwAffine<Jubjub>, K>
+where
+    F: PrimeField,
+    C: Commitment<F>,
+    Jubjub: SWCurveConfig<BaseField = F>,
 {
-    const N_COLUMNS: usize = <PiopProver<F, SwAffine<Jubjub>> as ProverPiop<F, C>>::N_COLUMNS;
+    const N_COLUMNS: usize = <PiopProver<F, SwAffine<Jubjub>, K> as ProverPiop<F, C>>::N_COLUMNS;
     const N_CONSTRAINTS: usize =
-        <PiopProver<F, SwAffine<Jubjub>> as ProverPiop<F, C>>::N_CONSTRAINTS;
-    type Instance = <PiopProver<F, SwAffine<Jubjub>> as ProverPiop<F, C>>::Instance;
+        <PiopProver<F, SwAffine<Jubjub>, K> as ProverPiop<F, C>>::N_CONSTRAINTS;
+    type Instance = <PiopProver<F, SwAffine<Jubjub>, K> as ProverPiop<F, C>>::Instance;
 
     fn precommitted_columns(&self) -> Vec<C> {
         self.fixed_columns_committed.as_vec()
     }
 
     fn evaluate_constraints_main(&self) -> Vec<F> {
-        vec![
-            self.inner_prod.evaluate_constraints_main(),
-            self.cond_add.evaluate_constraints_main(),
-            self.booleanity.evaluate_constraints_main(),
-            self.cond_add_acc_x.evaluate_constraints_main(),
-            self.cond_add_acc_y.evaluate_constraints_main(),
-            self.inner_prod_acc.evaluate_constraints_main(),
-        ]
-        .concat()
+        self._evaluate_constraints_main()
     }
 
     fn lin_poly_commitment(&self, agg_coeffs: &[F]) -> (Vec<F>, Vec<C>) {
         assert_eq!(agg_coeffs.len(), Self::N_CONSTRAINTS);
-
-        let inner_prod_acc = self.witness_columns_committed.inn_prod_acc.clone();
-        let inner_prod_coeff = agg_coeffs[0] * self.inner_prod.not_last;
-
-        let cond_add_acc_x = self.witness_columns_committed.cond_add_acc[0].clone();
-        let cond_add_acc_y = self.witness_columns_committed.cond_add_acc[1].clone();
-        let (c_acc_x, c_acc_y) = self.cond_add.acc_coeffs_1();
-        let mut cond_add_x_coeff = agg_coeffs[1] * c_acc_x;
-        let mut cond_add_y_coeff = agg_coeffs[1] * c_acc_y;
-        let (c_acc_x, c_acc_y) = self.cond_add.acc_coeffs_2();
-        cond_add_x_coeff += agg_coeffs[2] * c_acc_x;
-        cond_add_y_coeff += agg_coeffs[2] * c_acc_y;
-
-        (
-            vec![inner_prod_coeff, cond_add_x_coeff, cond_add_y_coeff],
-            vec![inner_prod_acc.clone(), cond_add_acc_x, cond_add_acc_y],
-        )
+        self._lin_poly_commitment(agg_coeffs)
     }
 
     fn domain_evaluated(&self) -> &EvaluatedDomain<F> {
@@ -0,0 +1,447 @@
+//! Variant of the ring PIOP proving that the weight (e.g. the stake) of the prover's key
+//! is at least a public threshold, without revealing the key.
+//!
+//! The weights are given by a fixed column of the ring, see `index_weighted`,
+//! so the verifier gets the commitment to it, and to the powers of 2 column, from the `WeightedVerifierKey`.
+//! The weighted keys wrap the keys of the plain ring, so the encoding of the latter doesn't depend on the weights.
+//! On top of the ring constraints, the weight of the key highlighted by the bits column
+//! is extracted with the inner product of the bits and the weights columns,
+//! then its difference with the threshold is range checked to be `WEIGHT_BITLEN`-bit.
+
+use ark_ec::pairing::Pairing;
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{PrimeField, Zero};
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::Evaluations;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::marker::PhantomData;
+use ark_std::{vec, vec::Vec};
+use w3f_pcs::pcs::commitment::WrappedAffine;
+use w3f_pcs::pcs::kzg::params::RawKzgVerifierKey;
+use w3f_pcs::pcs::kzg::KZG;
+use w3f_pcs::pcs::{Commitment, PCS};
+
+use w3f_plonk_common::domain::{Domain, EvaluatedDomain};
+use w3f_plonk_common::gadgets::fixed_cells::{FixedCells, FixedCellsValues};
+use w3f_plonk_common::gadgets::inner_prod::{InnerProd, InnerProdValues};
+use w3f_plonk_common::gadgets::range_check::{RangeCheck, RangeCheckValues};
+use w3f_plonk_common::gadgets::{ProverGadget, VerifierGadget};
+use w3f_plonk_common::piop::{ProverPiop, VerifierPiop};
+use w3f_plonk_common::{Column, ColumnsCommited, ColumnsEvaluated, FieldColumn};
+
+use crate::piop::params::{PiopParams, WEIGHT_BITLEN};
+use crate::piop::{
+    FixedColumns, FixedColumnsCommitted, PiopProver, PiopVerifier, ProverKey, RingCommitments,
+    RingEvaluations, VerifierKey,
+};
+use crate::ring::{ring_id, WeightedRing};
+use crate::RingId;
+
+// Index of the weights column in the list of columns, right after the precommitted ring columns.
+// The powers of 2 column follows.
+const WEIGHTS_INDEX: usize = 3;
+
+// Commitments to the weight columns (see below), a part of the `WeightedVerifierKey`.
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize, PartialEq, Eq, Debug)]
+pub struct WeightsCommitted<F: PrimeField, C: Commitment<F>> {
+    pub weights: C,
+    pub powers_of_2: C,
+    pub phantom: PhantomData<F>,
+}
+
+/// Public weights of the ring members, and the powers of 2 column used to range check them.
+/// See `PiopParams::ring_weights` and `Ring::with_weights`.
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct RingWeights<F: PrimeField> {
+    pub weights: FieldColumn<F>,
+    pub powers_of_2: FieldColumn<F>,
+}
+
+impl<F: PrimeField> RingWeights<F> {
+    pub fn commit<C: Commitment<F>>(
+        &self,
+        commit: impl Fn(&DensePolynomial<F>) -> C,
+    ) -> WeightsCommitted<F, C> {
+        WeightsCommitted {
+            weights: commit(self.weights.as_poly()),
+            powers_of_2: commit(self.powers_of_2.as_poly()),
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<C: CurveGroup> WeightsCommitted<C::ScalarField, WrappedAffine<C>> {
+    pub fn from_ring<KzgCurve, G>(ring: &WeightedRing<C::ScalarField, KzgCurve, G>) -> Self
+    where
+        KzgCurve: Pairing<ScalarField = C::ScalarField, G1Affine = C::Affine>,
+        G: AffineRepr<BaseField = C::ScalarField>,
+    {
+        Self {
+            weights: WrappedAffine(ring.weights[0]),
+            powers_of_2: WrappedAffine(ring.weights[1]),
+            phantom: PhantomData,
+        }
+    }
+}
+
+/// `ProverKey` of a weighted ring, see `index_weighted`.
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct WeightedProverKey<F: PrimeField, CS: PCS<F>, G: AffineRepr<BaseField = F>> {
+    pub prover_key: ProverKey<F, CS, G>,
+    pub weights: RingWeights<F>,
+    pub weights_committed: WeightsCommitted<F, CS::C>,
+}
+
+impl<F: PrimeField, CS: PCS<F>, G: AffineRepr<BaseField = F>> Clone
+    for WeightedProverKey<F, CS, G>
+{
+    fn clone(&self) -> Self {
+        Self {
+            prover_key: self.prover_key.clone(),
+            weights: self.weights.clone(),
+            weights_committed: self.weights_committed.clone(),
+        }
+    }
+}
+
+impl<F: PrimeField, CS: PCS<F>, G: AffineRepr<BaseField = F>> WeightedProverKey<F, CS, G> {
+    pub fn verifier_key(&self) -> WeightedVerifierKey<F, CS> {
+        WeightedVerifierKey {
+            verifier_key: self.prover_key.verifier_key.clone(),
+            weights: self.weights_committed.clone(),
+        }
+    }
+}
+
+/// `VerifierKey` of a weighted ring, see `index_weighted`.
+/// Used in the Fiat-Shamir transform as a whole, so the proofs are bound to the weights.
+#[derive(Debug, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct WeightedVerifierKey<F: PrimeField, CS: PCS<F>> {
+    pub verifier_key: VerifierKey<F, CS>,
+    pub weights: WeightsCommitted<F, CS::C>,
+}
+
+impl<F: PrimeField, CS: PCS<F>> Clone for WeightedVerifierKey<F, CS> {
+    fn clone(&self) -> Self {
+        Self {
+            verifier_key: self.verifier_key.clone(),
+            weights: self.weights.clone(),
+        }
+    }
+}
+
+impl<E: Pairing> WeightedVerifierKey<E::ScalarField, KZG<E>> {
+    pub fn from_ring_and_kzg_vk<G: AffineRepr<BaseField = E::ScalarField>>(
+        ring: &WeightedRing<E::ScalarField, E, G>,
+        kzg_vk: RawKzgVerifierKey<E>,
+    ) -> Self {
+        Self {
+            verifier_key: VerifierKey::from_ring_and_kzg_vk(&ring.ring, kzg_vk),
+            weights: WeightsCommitted::from_ring(ring),
+        }
+    }
+
+    /// Same as `WeightedRing::id` of the ring the key is built from, see `VerifierKey::id`.
+    pub fn id<G: AffineRepr<BaseField = E::ScalarField>>(
+        &self,
+        piop_params: &PiopParams<G>,
+        curr_keys: usize,
+    ) -> RingId {
+        let committed = &self.verifier_key.fixed_columns_committed;
+        ring_id(
+            &[
+                committed.points[0][0].0,
+                committed.points[0][1].0,
+                committed.ring_selector.0,
+            ],
+            piop_params.keyset_part_size,
+            curr_keys,
+            &piop_params.padding,
+            Some(&[self.weights.weights.0, self.weights.powers_of_2.0]),
+        )
+    }
+}
+
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct WeightedRingCommitments<F: PrimeField, C: Commitment<F>> {
+    pub(crate) ring: RingCommitments<F, C>,
+    pub(crate) weight_acc: C,
+    pub(crate) range_bits: C,
+    pub(crate) range_acc: C,
+}
+
+impl<F: PrimeField, C: Commitment<F>> ColumnsCommited<F, C> for WeightedRingCommitments<F, C> {
+    fn to_vec(self) -> Vec<C> {
+        let mut commitments = self.ring.to_vec();
+        commitments.extend([self.weight_acc, self.range_bits, self.range_acc]);
+        commitments
+    }
+}
+
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct WeightedRingEvaluations<F: PrimeField> {
+    pub(crate) ring: RingEvaluations<F>,
+    pub(crate) weights: F,
+    pub(crate) powers_of_2: F,
+    pub(crate) weight_acc: F,
+    pub(crate) range_bits: F,
+    pub(crate) range_acc: F,
+}
+
+impl<F: PrimeField> ColumnsEvaluated<F> for WeightedRingEvaluations<F> {
+    fn to_vec(self) -> Vec<F> {
+        let mut evals = self.ring.to_vec();
+        evals.insert(WEIGHTS_INDEX, self.weights);
+        evals.insert(WEIGHTS_INDEX + 1, self.powers_of_2);
+        evals.extend([self.weight_acc, self.range_bits, self.range_acc]);
+        evals
+    }
+}
+
+pub struct WeightedPiopProver<F: PrimeField, G: AffineRepr<BaseField = F>> {
+    piop: PiopProver<F, G>,
+    weights: RingWeights<F>,
+    threshold: u64,
+    // Extracts the prover's weight to the last cell of the accumulator.
+    weight: InnerProd<F>,
+    range_check: RangeCheck<F>,
+}
+
+impl<F: PrimeField, G: AffineRepr<BaseField = F>> WeightedPiopProver<F, G> {
+    pub fn build(
+        params: &PiopParams<G>,
+        fixed_columns: FixedColumns<F, G>,
+        weights: RingWeights<F>,
+        threshold: u64,
+        prover_index_in_keys: usize,
+        secret: G::ScalarField,
+    ) -> Self {
+        let piop = PiopProver::build(params, fixed_columns, prover_index_in_keys, secret);
+        let domain = &params.domain;
+        let weight = InnerProd::init(weights.weights.clone(), piop.bits().col.clone(), domain);
+        let range_check = RangeCheck::init(
+            weight.acc.clone(),
+            F::from(threshold),
+            weights.powers_of_2.clone(),
+            WEIGHT_BITLEN,
+            domain,
+        );
+        Self {
+            piop,
+            weights,
+            threshold,
+            weight,
+            range_check,
+        }
+    }
+}
+
+impl<F, C, G> ProverPiop<F, C> for WeightedPiopProver<F, G>
+where
+    F: PrimeField,
+    C: Commitment<F>,
+    G: AffineRepr<BaseField = F>,
+    PiopProver<F, G>: ProverPiop<
+        F,
+        C,
+        Commitments = RingCommitments<F, C>,
+        Evaluations = RingEvaluations<F>,
+        Instance = [G; 1],
+    >,
+{
+    const N_COLUMNS: usize = <PiopProver<F, G> as ProverPiop<F, C>>::N_COLUMNS + 5;
+    const N_CONSTRAINTS: usize = <PiopProver<F, G> as ProverPiop<F, C>>::N_CONSTRAINTS + 6;
+
+    type Commitments = WeightedRingCommitments<F, C>;
+    type Evaluations = WeightedRingEvaluations<F>;
+    // The threshold is a part of the instance, so that it gets into the transcript.
+    // The commitments to the weights get there with the verifier key.
+    type Instance = (G, u64);
+
+    fn committed_columns<Fun: Fn(&DensePolynomial<F>) -> C>(
+        &self,
+        commit: Fun,
+    ) -> Self::Commitments {
+        let ring = self.piop.committed_columns(&commit);
+        let weight_acc = commit(self.weight.acc.as_poly());
+        let [range_bits, range_acc] = self
+            .range_check
+            .witness_columns()
+            .iter()
+            .map(&commit)
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap();
+        WeightedRingCommitments {
+            ring,
+            weight_acc,
+            range_bits,
+            range_acc,
+        }
+    }
+
+    fn columns(&self) -> Vec<DensePolynomial<F>> {
+        let mut columns = ProverPiop::<F, C>::columns(&self.piop);
+        columns.insert(WEIGHTS_INDEX, self.weights.weights.as_poly().clone());
+        columns.insert(
+            WEIGHTS_INDEX + 1,
+            self.weights.powers_of_2.as_poly().clone(),
+        );
+        columns.push(self.weight.acc.as_poly().clone());
+        columns.extend(self.range_check.witness_columns());
+        columns
+    }
+
+    fn columns_evaluated(&self, zeta: &F) -> Self::Evaluations {
+        WeightedRingEvaluations {
+            ring: ProverPiop::<F, C>::columns_evaluated(&self.piop, zeta),
+            weights: self.weights.weights.evaluate(zeta),
+            powers_of_2: self.weights.powers_of_2.evaluate(zeta),
+            weight_acc: self.weight.acc.evaluate(zeta),
+            range_bits: self.range_check.bits().evaluate(zeta),
+            range_acc: self.range_check.acc().evaluate(zeta),
+        }
+    }
+
+    fn constraints(&self) -> Vec<Evaluations<F>> {
+        let domain = ProverPiop::<F, C>::domain(&self.piop);
+        vec![
+            ProverPiop::<F, C>::constraints(&self.piop),
+            self.weight.constraints(),
+            vec![FixedCells::constraint_cell(
+                &self.weight.acc,
+                &domain.l_first(),
+                0,
+                F::zero(),
+            )],
+            self.range_check.constraints(),
+        ]
+        .concat()
+    }
+
+    fn constraints_lin(&self, zeta: &F) -> Vec<DensePolynomial<F>> {
+        vec![
+            ProverPiop::<F, C>::constraints_lin(&self.piop, zeta),
+            self.weight.constraints_linearized(zeta),
+            vec![DensePolynomial::zero()],
+            self.range_check.constraints_linearized(zeta),
+        ]
+        .concat()
+    }
+
+    fn domain(&self) -> &Domain<F> {
+        ProverPiop::<F, C>::domain(&self.piop)
+    }
+
+    fn result(&self) -> Self::Instance {
+        (ProverPiop::<F, C>::result(&self.piop)[0], self.threshold)
+    }
+}
+
+pub struct WeightedPiopVerifier<F: PrimeField, C: Commitment<F>, P: AffineRepr<BaseField = F>> {
+    piop: PiopVerifier<F, C, P>,
+    weights: WeightsCommitted<F, C>,
+    witness_columns_committed: WeightedRingCommitments<F, C>,
+    weight: InnerProdValues<F>,
+    weight_acc_first: F,
+    range_check: RangeCheckValues<F>,
+}
+
+impl<F: PrimeField, C: Commitment<F>, P: AffineRepr<BaseField = F>> WeightedPiopVerifier<F, C, P> {
+    pub fn init(
+        domain_evals: EvaluatedDomain<F>,
+        fixed_columns_committed: FixedColumnsCommitted<F, C>,
+        weights: WeightsCommitted<F, C>,
+        threshold: u64,
+        witness_columns_committed: WeightedRingCommitments<F, C>,
+        all_columns_evaluated: WeightedRingEvaluations<F>,
+        init: (F, F),
+        result: (F, F),
+    ) -> Self {
+        let evals = &all_columns_evaluated;
+        let weight = InnerProdValues {
+            a: evals.weights,
+            b: evals.ring.bits,
+            not_last: domain_evals.not_last_row,
+            acc: evals.weight_acc,
+        };
+        let weight_acc_first =
+            FixedCellsValues::evaluate_for_cell(evals.weight_acc, domain_evals.l_first, F::zero());
+        let range_check = RangeCheckValues {
+            col: evals.weight_acc,
+            lower: F::from(threshold),
+            bits: evals.range_bits,
+            powers_of_2: evals.powers_of_2,
+            acc: evals.range_acc,
+            not_last: domain_evals.not_last_row,
+            l_first: domain_evals.l_first,
+            l_last: domain_evals.l_last,
+        };
+        let piop = PiopVerifier::init(
+            domain_evals,
+            fixed_columns_committed,
+            witness_columns_committed.ring.clone(),
+            all_columns_evaluated.ring,
+            init,
+            [result],
+        );
+        Self {
+            piop,
+            weights,
+            witness_columns_committed,
+            weight,
+            weight_acc_first,
+            range_check,
+        }
+    }
+}
+
+impl<F, C, P> VerifierPiop<F, C> for WeightedPiopVerifier<F, C, P>
+where
+    F: PrimeField,
+    C: Commitment<F>,
+    P: AffineRepr<BaseField = F>,
+    PiopVerifier<F, C, P>: VerifierPiop<F, C, Instance = [P; 1]>,
+{
+    const N_COLUMNS: usize = <PiopVerifier<F, C, P> as VerifierPiop<F, C>>::N_COLUMNS + 5;
+    const N_CONSTRAINTS: usize = <PiopVerifier<F, C, P> as VerifierPiop<F, C>>::N_CONSTRAINTS + 6;
+    type Instance = (P, u64);
+
+    fn precommitted_columns(&self) -> Vec<C> {
+        let mut columns = self.piop.precommitted_columns();
+        columns.extend([
+            self.weights.weights.clone(),
+            self.weights.powers_of_2.clone(),
+        ]);
+        columns
+    }
+
+    fn evaluate_constraints_main(&self) -> Vec<F> {
+        vec![
+            self.piop.evaluate_constraints_main(),
+            self.weight.evaluate_constraints_main(),
+            vec![self.weight_acc_first],
+            self.range_check.evaluate_constraints_main(),
+        ]
+        .concat()
+    }
+
+    fn lin_poly_commitment(&self, agg_coeffs: &[F]) -> (Vec<F>, Vec<C>) {
+        assert_eq!(agg_coeffs.len(), Self::N_CONSTRAINTS);
+        let n = <PiopVerifier<F, C, P> as VerifierPiop<F, C>>::N_CONSTRAINTS;
+        let (mut coeffs, mut commitments) = self.piop.lin_poly_commitment(&agg_coeffs[..n]);
+        // Of the constraints added, only the inner products contribute to the linearization polynomial:
+        // the weight accumulator, and the range check accumulator, that is the second constraint of the range check.
+        let not_last = self.piop.domain_evaluated().not_last_row;
+        coeffs.extend([agg_coeffs[n] * not_last, agg_coeffs[n + 3] * not_last]);
+        commitments.extend([
+            self.witness_columns_committed.weight_acc.clone(),
+            self.witness_columns_committed.range_acc.clone(),
+        ]);
+        (coeffs, commitments)
+    }
+
+    fn domain_evaluated(&self) -> &EvaluatedDomain<F> {
+        self.piop.domain_evaluated()
+    }
+}
@@ -0,0 +1,180 @@
+//! Parameters and empty rings for the standard configurations: Bandersnatch keys committed with KZG over BLS12-381,
+//! for the domains of size `2^9` to `2^16`.
+//!
+//! The points of the PIOP parameters are derived from fixed labels, see `Preset::compute`,
+//! and are the same for all the domain sizes. The empty rings depend on the SRS, that is identified by its digest.
+//! The table is generated with
+//!
+//! `cargo run --release --example presets --features presets -- <srs-file> > src/presets/table.rs`
+//!
+//! where the SRS file contains an uncompressed `URS<Bls12_381>` with at least `2^16` powers in G1.
+//! The table is checked against the values recomputed from the same SRS file by `test_presets`.
+
+use ark_bls12_381::Bls12_381;
+use ark_ed_on_bls12_381_bandersnatch::{EdwardsAffine, Fq};
+use ark_std::ops::Range;
+use ark_std::rand::RngCore;
+use ark_std::UniformRand;
+use w3f_pcs::pcs::kzg::urs::URS;
+
+use crate::piop::params::ZK_ROWS;
+use crate::ring::{Ring, RingBuilderKey};
+use crate::{Domain, PiopParams};
+
+mod table;
+
+/// Sizes of the domains the presets are provided for.
+pub const DOMAIN_SIZES: [usize; 8] = [
+    1 << 9,
+    1 << 10,
+    1 << 11,
+    1 << 12,
+    1 << 13,
+    1 << 14,
+    1 << 15,
+    1 << 16,
+];
+
+pub type PresetRing = Ring<Fq, Bls12_381, EdwardsAffine>;
+
+/// Parameters and the empty ring for a domain size.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Preset {
+    pub domain_size: usize,
+    /// Blinding base point, see `PiopParams::h`.
+    pub h: EdwardsAffine,
+    /// Summation base point, see `PiopParams::seed`.
+    pub seed: EdwardsAffine,
+    /// The empty ring, see `Ring::empty`. Holds the padding point.
+    pub empty_ring: PresetRing,
+    /// Digest of the SRS the empty ring is computed with, see `srs_digest`.
+    pub srs_digest: [u8; 32],
+}
+
+impl Preset {
+    /// Preset for the domain size, if any.
+    pub fn get(domain_size: usize) -> Option<&'static Preset> {
+        table::PRESETS
+            .iter()
+            .find(|preset| preset.domain_size == domain_size)
+    }
+
+    /// All the presets in the table.
+    pub fn all() -> &'static [Preset] {
+        table::PRESETS
+    }
+
+    pub fn piop_params(&self) -> PiopParams<EdwardsAffine> {
+        let domain = Domain::with_zk_rows(self.domain_size, ZK_ROWS);
+        PiopParams::setup(domain, self.h, self.seed, self.empty_ring.padding)
+    }
+
+    /// Checks that the empty ring was computed with the SRS.
+    pub fn check_srs(&self, srs: &URS<Bls12_381>) -> bool {
+        self.srs_digest == srs_digest(srs, self.domain_size)
+    }
+
+    /// Computes the preset for the domain size from scratch, with an `O(domain_size)`-MSM.
+    ///
+    /// - `srs`: Should contain at least `domain_size` powers in G1.
+    pub fn compute(domain_size: usize, srs: &URS<Bls12_381>) -> Self {
+        let domain = Domain::with_zk_rows(domain_size, ZK_ROWS);
+        let piop_params = PiopParams::setup(
+            domain,
+            derive_point(b"h"),
+            derive_point(b"seed"),
+            derive_point(b"padding"),
+        );
+        let ring_builder_key = RingBuilderKey::from_srs(srs, domain_size);
+        let srs_segment = |range: Range<usize>| Ok(ring_builder_key.lis_in_g1[range].to_vec());
+        let empty_ring = PresetRing::empty(&piop_params, srs_segment, ring_builder_key.g1);
+        Self {
+            domain_size,
+            h: piop_params.h,
+            seed: piop_params.seed,
+            empty_ring,
+            srs_digest: srs_digest(srs, domain_size),
+        }
+    }
+}
+
+/// Digest of the part of the SRS the rings over the domain are committed with, i.e. of the first `domain_size` powers in G1.
+pub fn srs_digest(srs: &URS<Bls12_381>, domain_size: usize) -> [u8; 32] {
+    let mut t = ark_transcript::Transcript::new_labeled(b"w3f-ring-proof-srs");
+    t.append(&srs.powers_in_g1[..domain_size]);
+    let mut digest = [0; 32];
+    t.challenge(b"srs_digest").fill_bytes(&mut digest);
+    digest
+}
+
+// A point of unknown discrete log, sampled with an rng seeded with the label.
+fn derive_point(label: &'static [u8]) -> EdwardsAffine {
+    let mut t = ark_transcript::Transcript::new_labeled(b"w3f-ring-proof-presets");
+    t.label(label);
+    EdwardsAffine::rand(&mut t.challenge(b"point"))
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_serialize::CanonicalDeserialize;
+    use ark_std::test_rng;
+    use w3f_pcs::pcs::kzg::KZG;
+    use w3f_pcs::pcs::PCS;
+
+    use super::*;
+
+    #[test]
+    fn test_presets() {
+        for domain_size in DOMAIN_SIZES {
+            let preset = Preset::get(domain_size);
+            assert!(
+                preset.is_some(),
+                "no preset for {domain_size}, regenerate the table"
+            );
+        }
+        assert_eq!(Preset::all().len(), DOMAIN_SIZES.len());
+        for preset in Preset::all() {
+            assert_eq!(preset.h, derive_point(b"h"));
+            assert_eq!(preset.seed, derive_point(b"seed"));
+            assert_eq!(preset.empty_ring.padding, derive_point(b"padding"));
+            let piop_params = preset.piop_params();
+            assert_eq!(preset.empty_ring.max_keys, piop_params.keyset_part_size);
+            assert_eq!(preset.empty_ring.curr_keys, 0);
+        }
+
+        // The SRS the table is generated with is not a part of the repo.
+        // If provided, the empty rings are recomputed.
+        if let Ok(path) = std::env::var("W3F_RING_PROOF_SRS") {
+            let file = std::fs::File::open(path).unwrap();
+            let srs = URS::<Bls12_381>::deserialize_uncompressed_unchecked(file).unwrap();
+            for preset in Preset::all() {
+                assert!(preset.check_srs(&srs));
+                assert_eq!(*preset, Preset::compute(preset.domain_size, &srs));
+            }
+        }
+    }
+
+    #[test]
+    fn test_compute_preset() {
+        let rng = &mut test_rng();
+
+        let domain_size = 1 << 9;
+
+        let srs = KZG::<Bls12_381>::setup(domain_size - 1, rng);
+        let preset = Preset::compute(domain_size, &srs);
+        assert!(preset.check_srs(&srs));
+
+        let piop_params = preset.piop_params();
+        let ring_builder_key = RingBuilderKey::from_srs(&srs, domain_size);
+        let srs_segment = |range: Range<usize>| Ok(ring_builder_key.lis_in_g1[range].to_vec());
+        let ring = PresetRing::empty(&piop_params, srs_segment, ring_builder_key.g1);
+        assert_eq!(preset.empty_ring, ring);
+        // The same ring, as baked into the table.
+        let unchecked =
+            PresetRing::empty_unchecked(domain_size, ring.cx, ring.cy, ring.selector, ring.padding);
+        assert_eq!(unchecked, ring);
+
+        let other_srs = KZG::<Bls12_381>::setup(domain_size - 1, rng);
+        assert!(!preset.check_srs(&other_srs));
+    }
+}
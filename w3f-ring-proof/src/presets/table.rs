@@ -0,0 +1,7 @@
+// Generated with `cargo run --release --example presets --features presets -- <srs-file>`, do not edit.
+//
+// Not generated yet: the SRS the presets are computed for is not a part of the repo, see `test_presets`.
+
+use super::Preset;
+
+pub(super) const PRESETS: &[Preset] = &[];
@@ -0,0 +1,175 @@
+use ark_ec::pairing::Pairing;
+use ark_ec::twisted_edwards::{Affine, TECurveConfig};
+use ark_std::sync::atomic::{AtomicUsize, Ordering};
+use ark_std::vec::Vec;
+use w3f_pcs::pcs::kzg::params::{KzgVerifierKey, RawKzgVerifierKey};
+use w3f_pcs::pcs::kzg::KZG;
+use w3f_pcs::pcs::{RawVerifierKey, PCS};
+use w3f_plonk_common::transcript::PlonkTranscript;
+
+use crate::multi_ring_batch_verifier::{BatchItem, BatchVerifier};
+use crate::piop::{FixedColumnsCommitted, VerifierKey};
+use crate::ring::{Ring, RingId};
+use crate::{PiopParams, RingProof};
+
+// What is cached per ring.
+struct RegisteredRing<E: Pairing, T> {
+    id: RingId,
+    fixed_columns_committed:
+        FixedColumnsCommitted<E::ScalarField, <KZG<E> as PCS<E::ScalarField>>::C>,
+    transcript_prelude: T,
+    // The value of the registry clock when the ring was last used.
+    last_used: AtomicUsize,
+}
+
+/// Verifier for proofs against many rings, that share the SRS and the PIOP parameters.
+///
+/// Unlike a `RingVerifier` per ring, prepares the KZG verifier key once,
+/// and keeps only the commitment and the transcript prelude for every ring registered.
+/// Holds at most `capacity` rings, evicting the least recently used one on overflow.
+///
+/// Verification doesn't mutate the registry, only the usage stamps of the rings, that are atomic,
+/// so the registry can be shared between threads.
+pub struct RingVerifierRegistry<E, J, T>
+where
+    E: Pairing,
+    J: TECurveConfig<BaseField = E::ScalarField>,
+    T: PlonkTranscript<E::ScalarField, KZG<E>>,
+{
+    pcs_raw_vk: RawKzgVerifierKey<E>,
+    piop_params: PiopParams<Affine<J>>,
+    empty_transcript: T,
+    // The prepared key shared between the rings.
+    pcs_vk: KzgVerifierKey<E>,
+    rings: Vec<RegisteredRing<E, T>>,
+    capacity: usize,
+    // Counts the uses of the rings, to find the least recently used one.
+    clock: AtomicUsize,
+}
+
+impl<E, J, T> RingVerifierRegistry<E, J, T>
+where
+    E: Pairing,
+    J: TECurveConfig<BaseField = E::ScalarField>,
+    T: PlonkTranscript<E::ScalarField, KZG<E>>,
+{
+    pub fn new(
+        pcs_raw_vk: RawKzgVerifierKey<E>,
+        piop_params: PiopParams<Affine<J>>,
+        empty_transcript: T,
+        capacity: usize,
+    ) -> Self {
+        assert!(capacity > 0, "the registry should fit at least 1 ring");
+        let pcs_vk = pcs_raw_vk.prepare();
+        Self {
+            pcs_raw_vk,
+            piop_params,
+            empty_transcript,
+            pcs_vk,
+            rings: Vec::with_capacity(capacity),
+            capacity,
+            clock: AtomicUsize::new(0),
+        }
+    }
+
+    /// Registers the ring, evicting the least recently used ring if the registry is full.
+    /// Returns the identifier the proofs against the ring should be routed by, see `Ring::id`.
+    pub fn register(&mut self, ring: &Ring<E::ScalarField, E, Affine<J>>) -> RingId {
+        let id = ring.id();
+        if let Some(ring) = self.find(&id) {
+            ring.last_used.store(self.tick(), Ordering::Relaxed);
+            return id;
+        }
+        if self.rings.len() == self.capacity {
+            let lru = (0..self.rings.len())
+                .min_by_key(|&i| self.rings[i].last_used.load(Ordering::Relaxed))
+                .unwrap();
+            self.rings.swap_remove(lru);
+        }
+        let last_used = AtomicUsize::new(self.tick());
+        let commitment = FixedColumnsCommitted::from_ring(ring);
+        // Same as in `RingVerifier::init`, so that the proofs are interchangeable.
+        let verifier_key =
+            VerifierKey::from_commitment_and_kzg_vk(commitment.clone(), self.pcs_raw_vk.clone());
+        let mut transcript_prelude = self.empty_transcript.clone();
+        transcript_prelude._add_serializable(b"vk", &verifier_key);
+        self.rings.push(RegisteredRing {
+            id,
+            fixed_columns_committed: commitment,
+            transcript_prelude,
+            last_used,
+        });
+        id
+    }
+
+    pub fn contains(&self, ring_id: &RingId) -> bool {
+        self.find(ring_id).is_some()
+    }
+
+    /// Number of the rings registered.
+    pub fn len(&self) -> usize {
+        self.rings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rings.is_empty()
+    }
+
+    /// Returns an empty batch the proofs against the registered rings can be pushed into, see `push`.
+    pub fn new_batch(&self) -> BatchVerifier<E, T> {
+        BatchVerifier::new(self.pcs_vk.clone(), self.empty_transcript.clone())
+    }
+
+    /// Adds the proof against the ring to the batch. Fails if the ring is not registered.
+    pub fn push(
+        &self,
+        batch: &mut BatchVerifier<E, T>,
+        ring_id: &RingId,
+        proof: RingProof<E::ScalarField, KZG<E>>,
+        result: Affine<J>,
+    ) -> Result<(), ()> {
+        let item = self.prepare(ring_id, proof, result)?;
+        batch.push_prepared(item);
+        Ok(())
+    }
+
+    /// Verifies the proof against the ring. Returns `false` if the ring is not registered.
+    pub fn verify(
+        &self,
+        ring_id: &RingId,
+        proof: RingProof<E::ScalarField, KZG<E>>,
+        result: Affine<J>,
+    ) -> bool {
+        let mut batch = self.new_batch();
+        self.push(&mut batch, ring_id, proof, result).is_ok() && batch.verify()
+    }
+
+    pub fn piop_params(&self) -> &PiopParams<Affine<J>> {
+        &self.piop_params
+    }
+
+    fn prepare(
+        &self,
+        ring_id: &RingId,
+        proof: RingProof<E::ScalarField, KZG<E>>,
+        result: Affine<J>,
+    ) -> Result<BatchItem<E, J>, ()> {
+        let ring = self.find(ring_id).ok_or(())?;
+        ring.last_used.store(self.tick(), Ordering::Relaxed);
+        Ok(BatchItem::from_parts(
+            &ring.transcript_prelude,
+            &self.piop_params,
+            &ring.fixed_columns_committed,
+            proof,
+            result,
+        ))
+    }
+
+    fn find(&self, ring_id: &RingId) -> Option<&RegisteredRing<E, T>> {
+        self.rings.iter().find(|ring| ring.id == *ring_id)
+    }
+
+    fn tick(&self) -> usize {
+        self.clock.fetch_add(1, Ordering::Relaxed)
+    }
+}
@@ -1,21 +1,30 @@
 use ark_ec::pairing::Pairing;
 use ark_ec::{AffineRepr, CurveGroup, VariableBaseMSM};
-use ark_ff::PrimeField;
-use ark_poly::EvaluationDomain;
+use ark_ff::{batch_inversion, PrimeField, Zero};
+use ark_poly::univariate::DensePolynomial;
+use ark_poly::{DenseUVPolynomial, EvaluationDomain};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::array;
 use ark_std::fmt;
 use ark_std::iter;
 use ark_std::ops::Range;
-use ark_std::vec::Vec;
+use ark_std::rand::RngCore;
+use ark_std::{vec, vec::Vec};
+use w3f_pcs::pcs::commitment::WrappedAffine;
+use w3f_pcs::pcs::kzg::params::RawKzgVerifierKey;
 use w3f_pcs::pcs::kzg::urs::URS;
-use w3f_pcs::pcs::PcsParams;
+use w3f_pcs::pcs::kzg::KZG;
+use w3f_pcs::pcs::{PcsParams, RawVerifierKey, PCS};
 
-use crate::piop::params::ZK_ROWS;
+use crate::piop::params::{TupleParams, WEIGHT_BITLEN, ZK_ROWS};
 
 use crate::PiopParams;
 
 const IDLE_ROWS: usize = ZK_ROWS + 1;
 
+/// 32-byte handle of a ring, see `Ring::id`.
+pub type RingId = [u8; 32];
+
 /// Commitment to a list of VRF public keys as is used as a public input to the ring proof SNARK verifier.
 ///
 /// The VRF keys are (inner) curve points that we represent in the affine Twisted Edwards coordinates.
@@ -51,9 +60,13 @@ impl<F: PrimeField, KzgCurve: Pairing<ScalarField = F>, G: AffineRepr<BaseField
     for Ring<F, KzgCurve, G>
 {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Ring(id=0x")?;
+        for byte in self.id() {
+            write!(f, "{byte:02x}")?;
+        }
         write!(
             f,
-            "Ring(curr_keys={}, max_keys{})",
+            ", curr_keys={}, max_keys={})",
             self.curr_keys, self.max_keys
         )
     }
@@ -124,15 +137,8 @@ impl<F: PrimeField, KzgCurve: Pairing<ScalarField = F>, G: AffineRepr<BaseField
     ) {
         let new_size = self.curr_keys + keys.len();
         assert!(new_size <= self.max_keys);
-        let (padding_x, padding_y) = self.padding.xy().unwrap();
-        let (xs, ys): (Vec<F>, Vec<F>) = keys
-            .iter()
-            .map(|p| p.xy().unwrap())
-            .map(|(x, y)| (x - padding_x, y - padding_y))
-            .unzip();
         let srs_segment = &srs(self.curr_keys..self.curr_keys + keys.len()).unwrap();
-        let cx_delta = KzgCurve::G1::msm(srs_segment, &xs).unwrap();
-        let cy_delta = KzgCurve::G1::msm(srs_segment, &ys).unwrap();
+        let (cx_delta, cy_delta) = commit_keys::<KzgCurve::G1, G>(keys, self.padding, srs_segment);
 
         let (new_cx, new_cy) = {
             let affine = KzgCurve::G1::normalize_batch(&[self.cx + cx_delta, self.cy + cy_delta]);
@@ -144,6 +150,124 @@ impl<F: PrimeField, KzgCurve: Pairing<ScalarField = F>, G: AffineRepr<BaseField
         self.curr_keys = new_size;
     }
 
+    /// Replaces the key at index `i`.
+    ///
+    /// - `i`: Index of the key to replace, should be less than `self.curr_keys`.
+    /// - `old_key`: The key currently at index `i`.
+    /// - `new_key`: The key to put at index `i`.
+    /// - `srs`: Should return `srs[range]` for `range = (i..i + 1)`
+    pub fn replace_key(
+        &mut self,
+        i: usize,
+        old_key: G,
+        new_key: G,
+        srs: impl Fn(Range<usize>) -> Result<Vec<KzgCurve::G1Affine>, ()>,
+    ) {
+        assert!(i < self.curr_keys);
+        let (old_x, old_y) = old_key.xy().unwrap();
+        let (new_x, new_y) = new_key.xy().unwrap();
+        let li = srs(i..i + 1).unwrap()[0];
+        let (new_cx, new_cy) = {
+            let affine = KzgCurve::G1::normalize_batch(&[
+                self.cx + li * (new_x - old_x),
+                self.cy + li * (new_y - old_y),
+            ]);
+            (affine[0], affine[1])
+        };
+        self.cx = new_cx;
+        self.cy = new_cy;
+    }
+
+    /// Commits to the selector of a subset of the ring slots, e.g. of the slots of the members with a certain role.
+    /// The commitment is the sum of the Lagrangian SRS elements at the slots, see `PiopParams::subset_selector`.
+    ///
+    /// - `slots`: Indices of the slots in the subset, each should be less than `self.max_keys`.
+    /// - `srs`: Should return `srs[range]` for `range = (i..i + 1)`, for every `i` in `slots`
+    pub fn subset_selector(
+        &self,
+        slots: &[usize],
+        srs: impl Fn(Range<usize>) -> Result<Vec<KzgCurve::G1Affine>, ()>,
+    ) -> KzgCurve::G1Affine {
+        slots
+            .iter()
+            .map(|&i| {
+                assert!(i < self.max_keys);
+                srs(i..i + 1).unwrap()[0]
+            })
+            .sum::<KzgCurve::G1>()
+            .into_affine()
+    }
+
+    /// Same as `subset_selector`, but for a range of slots.
+    ///
+    /// - `slots`: Range of the slots, should end not further than `self.max_keys`.
+    /// - `srs`: Should return `srs[range]` for `range = slots`
+    pub fn range_selector(
+        &self,
+        slots: Range<usize>,
+        srs: impl Fn(Range<usize>) -> Result<Vec<KzgCurve::G1Affine>, ()>,
+    ) -> KzgCurve::G1Affine {
+        assert!(slots.end <= self.max_keys);
+        srs(slots)
+            .unwrap()
+            .iter()
+            .sum::<KzgCurve::G1>()
+            .into_affine()
+    }
+
+    /// Commits to the weights of the ring members, see `PiopParams::weights`.
+    /// Same as the keys, the weights are committed with the Lagrangian SRS, so a single MSM is required.
+    ///
+    /// - `weights`: Weights of the ring members in the order of the keys.
+    /// - `srs`: Should return `srs[range]` for `range = (0..weights.len())`
+    pub fn weights_commitment(
+        &self,
+        weights: &[u64],
+        srs: impl Fn(Range<usize>) -> Result<Vec<KzgCurve::G1Affine>, ()>,
+    ) -> KzgCurve::G1Affine {
+        assert!(weights.len() <= self.max_keys);
+        let bases = srs(0..weights.len()).unwrap();
+        let weights: Vec<F> = weights.iter().map(|&w| F::from(w)).collect();
+        KzgCurve::G1::msm(&bases, &weights).unwrap().into_affine()
+    }
+
+    /// Commits to the column of the powers of 2 used to range check the weights, see `PiopParams::powers_of_2`.
+    /// Doesn't depend on the keys, so can be computed once.
+    ///
+    /// - `srs`: Should return `srs[range]` for `range = (0..WEIGHT_BITLEN)`
+    pub fn powers_of_2_commitment(
+        &self,
+        srs: impl Fn(Range<usize>) -> Result<Vec<KzgCurve::G1Affine>, ()>,
+    ) -> KzgCurve::G1Affine {
+        let bases = srs(0..WEIGHT_BITLEN).unwrap();
+        let mut power = F::one();
+        let powers: Vec<_> = (0..WEIGHT_BITLEN)
+            .map(|_| {
+                let p = power;
+                power.double_in_place();
+                p
+            })
+            .collect();
+        KzgCurve::G1::msm(&bases, &powers).unwrap().into_affine()
+    }
+
+    /// Makes the ring weighted, committing to the `weights` of the members, see `WeightedPiopProver`.
+    ///
+    /// - `weights`: Weights of the ring members in the order of the keys.
+    /// - `srs`: Should return `srs[range]` for `range = (0..weights.len())` and `range = (0..WEIGHT_BITLEN)`
+    pub fn with_weights(
+        self,
+        weights: &[u64],
+        srs: impl Fn(Range<usize>) -> Result<Vec<KzgCurve::G1Affine>, ()>,
+    ) -> WeightedRing<F, KzgCurve, G> {
+        let weights_commitment = self.weights_commitment(weights, &srs);
+        let powers_of_2_commitment = self.powers_of_2_commitment(&srs);
+        WeightedRing {
+            ring: self,
+            weights: [weights_commitment, powers_of_2_commitment],
+        }
+    }
+
     /// Builds the ring from the keys provided with 2 MSMs of size `keys.len() + scalar_bitlen + 5`.
     ///
     /// In some cases it may be beneficial to cash the empty ring, as updating it costs 2 MSMs of size `keys.len()`.
@@ -155,43 +279,8 @@ impl<F: PrimeField, KzgCurve: Pairing<ScalarField = F>, G: AffineRepr<BaseField
         keys: &[G],
         srs: &RingBuilderKey<F, KzgCurve>,
     ) -> Self {
-        let (padding_x, padding_y) = piop_params.padding.xy().unwrap(); // panics on inf, never happens
-        let powers_of_h = piop_params.power_of_2_multiples_of_h();
-
-        // Computes
-        // [(pk1 - padding), ..., (pkn - padding),
-        //  (H - padding), ..., (2^(s-1)HH - padding),
-        //  -padding, -padding, -padding, -padding,
-        //  padding].
-        let (xs, ys): (Vec<F>, Vec<F>) = keys
-            .iter()
-            .chain(&powers_of_h)
-            .map(|p| p.xy().unwrap())
-            .map(|(x, y)| (x - padding_x, y - padding_y))
-            .chain(iter::repeat((-padding_x, -padding_y)).take(4))
-            .chain(iter::once((padding_x, padding_y)))
-            .unzip();
-
-        // Composes the corresponding slices of the SRS.
-        let bases = [
-            &srs.lis_in_g1[..keys.len()],
-            &srs.lis_in_g1[piop_params.keyset_part_size..],
-            &[srs.g1.into()],
-        ]
-        .concat();
-
-        let cx = KzgCurve::G1::msm(&bases, &xs).unwrap();
-        let cy = KzgCurve::G1::msm(&bases, &ys).unwrap();
-        let selector_inv = srs.lis_in_g1[piop_params.keyset_part_size..]
-            .iter()
-            .sum::<KzgCurve::G1>();
-        let selector = srs.g1 - selector_inv;
-
-        let (cx, cy, selector) = {
-            let affine = KzgCurve::G1::normalize_batch(&[cx, cy, selector]);
-            (affine[0], affine[1], affine[2])
-        };
-
+        let [cx, cy, selector] =
+            commit_ring::<KzgCurve::G1, G>(piop_params, keys, &srs.lis_in_g1, srs.g1);
         Self {
             cx,
             cy,
@@ -202,10 +291,107 @@ impl<F: PrimeField, KzgCurve: Pairing<ScalarField = F>, G: AffineRepr<BaseField
         }
     }
 
+    /// Computes the updates of the slots that turn the ring of `old_keys`, that should be this ring,
+    /// into the ring of `new_keys`. The delta commits to the id of the resulting ring, see `Ring::apply`.
+    /// Fails if `old_keys` don't fill this ring, if `new_keys` don't fit it, or if `srs` fails.
+    ///
+    /// - `srs`: Should return `srs[range]` for `range = (i..i + 1)`, for every slot `i` changed
+    pub fn diff(
+        &self,
+        old_keys: &[G],
+        new_keys: &[G],
+        srs: impl Fn(Range<usize>) -> Result<Vec<KzgCurve::G1Affine>, ()>,
+    ) -> Result<RingDelta<G>, ()> {
+        if old_keys.len() != self.curr_keys || new_keys.len() > self.max_keys {
+            return Err(());
+        }
+        let key_at = |keys: &[G], i: usize| keys.get(i).copied().unwrap_or(self.padding);
+        let updates = (0..old_keys.len().max(new_keys.len()))
+            .map(|slot| SlotUpdate {
+                slot,
+                old: key_at(old_keys, slot),
+                new: key_at(new_keys, slot),
+            })
+            .filter(|update| update.old != update.new)
+            .collect();
+        let mut delta = RingDelta {
+            updates,
+            curr_keys: new_keys.len(),
+            target: None,
+        };
+        let mut target = self.clone();
+        target.apply(&delta, srs)?;
+        delta.target = Some(target.id());
+        Ok(delta)
+    }
+
+    /// Applies the delta computed with `Ring::diff`, with an MSM over the changed slots.
+    /// Fails, leaving the ring intact, if the delta doesn't fit the ring, if it contains the identity point,
+    /// or if the resulting ring is not the one the delta commits to.
+    ///
+    /// - `srs`: Should return `srs[range]` for `range = (i..i + 1)`, for every slot `i` changed
+    pub fn apply(
+        &mut self,
+        delta: &RingDelta<G>,
+        srs: impl Fn(Range<usize>) -> Result<Vec<KzgCurve::G1Affine>, ()>,
+    ) -> Result<(), ()> {
+        if delta.curr_keys > self.max_keys
+            || delta
+                .updates
+                .iter()
+                .any(|update| update.slot >= self.max_keys)
+        {
+            return Err(());
+        }
+        let bases = delta
+            .updates
+            .iter()
+            .map(|update| srs(update.slot..update.slot + 1).map(|li| li[0]))
+            .collect::<Result<Vec<_>, ()>>()?;
+        // The identity has no affine coordinates.
+        let (xs, ys): (Vec<F>, Vec<F>) = delta
+            .updates
+            .iter()
+            .map(|update| {
+                let (old_x, old_y) = update.old.xy().ok_or(())?;
+                let (new_x, new_y) = update.new.xy().ok_or(())?;
+                Ok((new_x - old_x, new_y - old_y))
+            })
+            .collect::<Result<Vec<_>, ()>>()?
+            .into_iter()
+            .unzip();
+        let cx_delta = KzgCurve::G1::msm(&bases, &xs).unwrap();
+        let cy_delta = KzgCurve::G1::msm(&bases, &ys).unwrap();
+        let affine = KzgCurve::G1::normalize_batch(&[self.cx + cx_delta, self.cy + cy_delta]);
+        let ring = Self {
+            cx: affine[0],
+            cy: affine[1],
+            curr_keys: delta.curr_keys,
+            ..self.clone()
+        };
+        if delta.target.is_some_and(|target| target != ring.id()) {
+            return Err(());
+        }
+        *self = ring;
+        Ok(())
+    }
+
     pub fn slots_left(&self) -> usize {
         self.max_keys - self.curr_keys
     }
 
+    /// Identifier of the ring: a domain-separated hash of the commitments, `max_keys`, `curr_keys` and the padding.
+    /// Same for the rings built from the same keys in the same order, however they are built.
+    pub fn id(&self) -> RingId {
+        ring_id(
+            &[self.cx, self.cy, self.selector],
+            self.max_keys,
+            self.curr_keys,
+            &self.padding,
+            None,
+        )
+    }
+
     pub const fn empty_unchecked(
         domain_size: usize,
         cx: KzgCurve::G1Affine,
@@ -223,6 +409,324 @@ impl<F: PrimeField, KzgCurve: Pairing<ScalarField = F>, G: AffineRepr<BaseField
             padding,
         }
     }
+
+    /// Proves that the `new` ring is obtained from this one by appending the `keys`,
+    /// so that the update can be checked without the Lagrangian SRS, see `Ring::verify_append`.
+    ///
+    /// The difference of the commitments commits to the polynomial `D(X) = sum((pk_i - padding).L_{curr_keys+i}(X))`,
+    /// that is opened at a Fiat-Shamir point. The coordinates are batched with a random coefficient.
+    /// Fails if one of the `keys` is the identity point.
+    ///
+    /// - `pcs_params`: The monomial SRS the Lagrangian SRS is computed from.
+    pub fn prove_append(
+        &self,
+        new: &Self,
+        keys: &[G],
+        piop_params: &PiopParams<G>,
+        pcs_params: &URS<KzgCurve>,
+    ) -> Result<RingUpdateProof<KzgCurve>, ()> {
+        let (r, z, _) = self.update_challenges(new, keys);
+        let (padding_x, padding_y) = self.padding.xy().unwrap();
+        let domain = piop_params.domain.domain();
+        let mut evals = vec![F::zero(); domain.size()];
+        for (i, key) in keys.iter().enumerate() {
+            let (x, y) = key.xy().ok_or(())?;
+            evals[self.curr_keys + i] = (x - padding_x) + r * (y - padding_y);
+        }
+        let poly = DensePolynomial::from_coefficients_vec(domain.ifft(&evals));
+        let opening = KZG::<KzgCurve>::open(&pcs_params.ck(), &poly, z).unwrap();
+        Ok(RingUpdateProof { opening })
+    }
+
+    /// Checks that the `new` ring is obtained from this one by appending the `keys`, see `Ring::prove_append`.
+    /// Unlike `Ring::append`, requires `O(1)` SRS elements, but `O(keys.len())` field operations.
+    /// Rejects if one of the `keys` is the identity point.
+    pub fn verify_append(
+        &self,
+        new: &Self,
+        keys: &[G],
+        piop_params: &PiopParams<G>,
+        proof: &RingUpdateProof<KzgCurve>,
+        kzg_vk: &RawKzgVerifierKey<KzgCurve>,
+    ) -> bool {
+        if new.max_keys != self.max_keys
+            || new.padding != self.padding
+            || new.selector != self.selector
+            || new.curr_keys != self.curr_keys + keys.len()
+            || new.curr_keys > new.max_keys
+        {
+            return false;
+        }
+        let (r, z, mut transcript) = self.update_challenges(new, keys);
+        let domain = piop_params.domain.domain();
+        let vanishing_at_z = domain.evaluate_vanishing_polynomial(z);
+        if vanishing_at_z.is_zero() {
+            return false;
+        }
+
+        // `L_j(z) = w^j/N.(z^N - 1)/(z - w^j)`
+        let slots = self.curr_keys..new.curr_keys;
+        let mut denoms: Vec<F> = slots.clone().map(|j| z - domain.element(j)).collect();
+        batch_inversion(&mut denoms);
+        let (padding_x, padding_y) = self.padding.xy().unwrap();
+        let Some(terms) = keys
+            .iter()
+            .zip(slots)
+            .zip(denoms)
+            .map(|((key, j), denom_inv)| {
+                let (x, y) = key.xy()?;
+                Some(((x - padding_x) + r * (y - padding_y)) * domain.element(j) * denom_inv)
+            })
+            .collect::<Option<Vec<F>>>()
+        else {
+            return false;
+        };
+        let value = terms.iter().sum::<F>() * vanishing_at_z * domain.size_inv();
+
+        let commitment = (new.cx.into_group() - self.cx) + (new.cy.into_group() - self.cy) * r;
+        KZG::<KzgCurve>::batch_verify(
+            &kzg_vk.prepare(),
+            vec![WrappedAffine(commitment.into_affine())],
+            vec![z],
+            vec![value],
+            vec![proof.opening.clone()],
+            &mut transcript.challenge(b"batch_verify"),
+        )
+        .is_ok()
+    }
+
+    // Fiat-Shamir challenges of the update proof: the coefficient batching the coordinates, and the opening point.
+    fn update_challenges(&self, new: &Self, keys: &[G]) -> (F, F, ark_transcript::Transcript) {
+        let mut t = ark_transcript::Transcript::new_labeled(b"w3f-ring-proof-ring-update");
+        t.label(b"old_ring");
+        t.append(self);
+        t.label(b"new_ring");
+        t.append(new);
+        t.label(b"keys");
+        t.append(&keys);
+        let r = t.challenge(b"r").read_reduce();
+        let z = t.challenge(b"z").read_reduce();
+        (r, z, t)
+    }
+}
+
+/// Ring with the weights of the members committed, see `Ring::with_weights`.
+/// The weights are kept apart, so the encoding and the id of the plain `Ring` don't depend on them.
+#[derive(Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct WeightedRing<
+    F: PrimeField,
+    KzgCurve: Pairing<ScalarField = F>,
+    G: AffineRepr<BaseField = F>,
+> {
+    pub ring: Ring<F, KzgCurve, G>,
+    /// KZG commitments to the weights of the ring members and to the powers of 2 column.
+    /// Not updated with the keys.
+    pub weights: [KzgCurve::G1Affine; 2],
+}
+
+impl<F: PrimeField, KzgCurve: Pairing<ScalarField = F>, G: AffineRepr<BaseField = F>>
+    WeightedRing<F, KzgCurve, G>
+{
+    /// Same as `Ring::id`, but hashes the weights commitments too.
+    pub fn id(&self) -> RingId {
+        let ring = &self.ring;
+        ring_id(
+            &[ring.cx, ring.cy, ring.selector],
+            ring.max_keys,
+            ring.curr_keys,
+            &ring.padding,
+            Some(&self.weights),
+        )
+    }
+}
+
+/// Proof that a ring is obtained from another by appending keys, see `Ring::prove_append`.
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct RingUpdateProof<E: Pairing> {
+    pub opening: <KZG<E> as PCS<E::ScalarField>>::Proof,
+}
+
+/// Update of a slot of the ring, see `RingDelta`.
+/// The slots past the keys hold the padding point.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct SlotUpdate<G: AffineRepr> {
+    pub slot: usize,
+    /// The key in the slot before the update.
+    pub old: G,
+    /// The key in the slot after the update.
+    pub new: G,
+}
+
+/// Difference between 2 rings, see `Ring::diff` and `Ring::apply`.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct RingDelta<G: AffineRepr> {
+    /// Updates of the slots that differ.
+    pub updates: Vec<SlotUpdate<G>>,
+    /// Number of the keys in the resulting ring.
+    pub curr_keys: usize,
+    /// Id of the resulting ring, if set, the delta is applied only if the resulting ring matches.
+    pub target: Option<RingId>,
+}
+
+/// Computes the identifier of the ring built from the keys, same as `Ring::with_keys(piop_params, keys, srs).id()`.
+pub fn ring_id_from_keys<
+    F: PrimeField,
+    KzgCurve: Pairing<ScalarField = F>,
+    G: AffineRepr<BaseField = F>,
+>(
+    piop_params: &PiopParams<G>,
+    keys: &[G],
+    srs: &RingBuilderKey<F, KzgCurve>,
+) -> RingId {
+    Ring::with_keys(piop_params, keys, srs).id()
+}
+
+/// See `Ring::id`.
+/// The weights are appended only for a weighted ring, so that the ids of the other rings don't depend on them.
+pub(crate) fn ring_id<C: CanonicalSerialize, G: CanonicalSerialize>(
+    commitments: &[C; 3],
+    max_keys: usize,
+    curr_keys: usize,
+    padding: &G,
+    weights: Option<&[C; 2]>,
+) -> RingId {
+    let mut t = ark_transcript::Transcript::new_labeled(b"w3f-ring-proof-ring-id");
+    t.label(b"commitments");
+    t.append(commitments);
+    t.label(b"max_keys");
+    t.append(&(max_keys as u64));
+    t.label(b"curr_keys");
+    t.append(&(curr_keys as u64));
+    t.label(b"padding");
+    t.append(padding);
+    if let Some(weights) = weights {
+        t.label(b"weights");
+        t.append(weights);
+    }
+    let mut id = RingId::default();
+    t.challenge(b"ring_id").fill_bytes(&mut id);
+    id
+}
+
+/// Commits to the coordinates of the vector of `keys` minus the `padding`,
+/// using `bases`, that should be the Lagrangian SRS segment corresponding to the slots of the keys.
+pub(crate) fn commit_keys<C: CurveGroup, G: AffineRepr<BaseField = C::ScalarField>>(
+    keys: &[G],
+    padding: G,
+    bases: &[C::Affine],
+) -> (C, C) {
+    let (padding_x, padding_y) = padding.xy().unwrap();
+    let (xs, ys): (Vec<C::ScalarField>, Vec<C::ScalarField>) = keys
+        .iter()
+        .map(|p| p.xy().unwrap())
+        .map(|(x, y)| (x - padding_x, y - padding_y))
+        .unzip();
+    let cx = C::msm(bases, &xs).unwrap();
+    let cy = C::msm(bases, &ys).unwrap();
+    (cx, cy)
+}
+
+/// Commits to the ring of the `keys` with 2 MSMs of size `keys.len() + scalar_bitlen + 5`, see `Ring::with_keys`.
+/// Returns the commitments to the x and y coordinates of the points column, and to the ring selector.
+///
+/// - `lis`: full-size Lagrangian SRS,
+/// - `g`: commitment to the constant `1` polynomial, i.e. the sum of the `lis`.
+pub(crate) fn commit_ring<C: CurveGroup, G: AffineRepr<BaseField = C::ScalarField>>(
+    piop_params: &PiopParams<G>,
+    keys: &[G],
+    lis: &[C::Affine],
+    g: C,
+) -> [C::Affine; 3] {
+    let (padding_x, padding_y) = piop_params.padding.xy().unwrap(); // panics on inf, never happens
+    let powers_of_h = piop_params.power_of_2_multiples_of_h();
+
+    // Computes
+    // [(pk1 - padding), ..., (pkn - padding),
+    //  (H - padding), ..., (2^(s-1)HH - padding),
+    //  -padding, -padding, -padding, -padding,
+    //  padding].
+    let (xs, ys): (Vec<C::ScalarField>, Vec<C::ScalarField>) = keys
+        .iter()
+        .chain(&powers_of_h)
+        .map(|p| p.xy().unwrap())
+        .map(|(x, y)| (x - padding_x, y - padding_y))
+        .chain(iter::repeat((-padding_x, -padding_y)).take(4))
+        .chain(iter::once((padding_x, padding_y)))
+        .unzip();
+
+    // Composes the corresponding slices of the SRS.
+    let bases = [
+        &lis[..keys.len()],
+        &lis[piop_params.keyset_part_size..],
+        &[g.into()],
+    ]
+    .concat();
+
+    let cx = C::msm(&bases, &xs).unwrap();
+    let cy = C::msm(&bases, &ys).unwrap();
+    let selector_inv = lis[piop_params.keyset_part_size..].iter().sum::<C>();
+    let selector = g - selector_inv;
+
+    let affine = C::normalize_batch(&[cx, cy, selector]);
+    [affine[0], affine[1], affine[2]]
+}
+
+/// Commitment to a list of `K`-tuples of keys, see `TupleParams`.
+///
+/// The `j`-th components of the tuples are committed to as a `Ring` built with the blinding base `hs[j]`.
+/// All the component rings share the padding and the selector.
+#[derive(Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct TupleRing<
+    F: PrimeField,
+    KzgCurve: Pairing<ScalarField = F>,
+    G: AffineRepr<BaseField = F>,
+    const K: usize,
+> {
+    pub components: [Ring<F, KzgCurve, G>; K],
+}
+
+impl<
+        F: PrimeField,
+        KzgCurve: Pairing<ScalarField = F>,
+        G: AffineRepr<BaseField = F>,
+        const K: usize,
+    > TupleRing<F, KzgCurve, G, K>
+{
+    /// Builds the ring from the key tuples provided with `2K` MSMs of size `keys.len() + scalar_bitlen + 5`.
+    ///
+    /// - `params`: SNARK parameters.
+    /// - `srs`: full-size Lagrangian SRS.
+    pub fn with_keys(
+        params: &TupleParams<G, K>,
+        keys: &[[G; K]],
+        srs: &RingBuilderKey<F, KzgCurve>,
+    ) -> Self {
+        let components = array::from_fn(|j| {
+            let keys: Vec<G> = keys.iter().map(|tuple| tuple[j]).collect();
+            Ring::with_keys(&params.component(j), &keys, srs)
+        });
+        Self { components }
+    }
+
+    /// Appends a set key tuple sequence to the ring.
+    ///
+    /// - `keys`: Key tuples to append.
+    /// - `srs`: Should return `srs[range]` for `range = (self.curr_keys()..self.curr_keys() + keys.len())`
+    pub fn append(
+        &mut self,
+        keys: &[[G; K]],
+        srs: impl Fn(Range<usize>) -> Result<Vec<KzgCurve::G1Affine>, ()>,
+    ) {
+        for (j, ring) in self.components.iter_mut().enumerate() {
+            let keys: Vec<G> = keys.iter().map(|tuple| tuple[j]).collect();
+            ring.append(&keys, &srs);
+        }
+    }
+
+    /// Number of key tuples "stored" in this commitment.
+    pub fn curr_keys(&self) -> usize {
+        self.components[0].curr_keys
+    }
 }
 
 #[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
@@ -246,10 +750,7 @@ impl<F: PrimeField, KzgCurve: Pairing<ScalarField = F>> RingBuilderKey<F, KzgCur
 mod tests {
     use ark_bls12_381::{Bls12_381, Fr, G1Affine};
     use ark_ed_on_bls12_381_bandersnatch::EdwardsAffine;
-    use ark_std::test_rng;
-    use w3f_pcs::pcs::kzg::urs::URS;
-    use w3f_pcs::pcs::kzg::KZG;
-    use w3f_pcs::pcs::PCS;
+    use ark_std::{test_rng, UniformRand};
 
     use w3f_plonk_common::test_helpers::random_vec;
 
@@ -302,6 +803,157 @@ mod tests {
         assert_eq!(ring, same_ring);
     }
 
+    #[test]
+    fn test_ring_id() {
+        let rng = &mut test_rng();
+
+        let domain_size = 1 << 9;
+
+        let pcs_params = KZG::<Bls12_381>::setup(domain_size - 1, rng);
+        let ring_builder_key = RingBuilderKey::from_srs(&pcs_params, domain_size);
+        let srs = |range: Range<usize>| Ok(ring_builder_key.lis_in_g1[range].to_vec());
+        let piop_params = PiopParams::rand(domain_size, rng);
+        let keys = random_vec::<EdwardsAffine, _>(10, rng);
+
+        let ring = TestRing::with_keys(&piop_params, &keys, &ring_builder_key);
+        let mut same_ring = TestRing::empty(&piop_params, srs, ring_builder_key.g1);
+        same_ring.append(&keys[..5], srs);
+        same_ring.append(&keys[5..], srs);
+        assert_eq!(ring.id(), same_ring.id());
+        assert_eq!(
+            ring.id(),
+            ring_id_from_keys(&piop_params, &keys, &ring_builder_key)
+        );
+        let (_, verifier_key) =
+            crate::piop::index::<_, KZG<Bls12_381>, _>(&pcs_params, &piop_params, &keys);
+        assert_eq!(ring.id(), verifier_key.id(&piop_params, keys.len()));
+
+        let mut other_ring = ring.clone();
+        other_ring.curr_keys -= 1;
+        assert_ne!(ring.id(), other_ring.id());
+        let mut other_keys = keys.clone();
+        other_keys.swap(0, 1);
+        assert_ne!(
+            ring.id(),
+            ring_id_from_keys(&piop_params, &other_keys, &ring_builder_key)
+        );
+    }
+
+    #[test]
+    fn test_ring_delta() {
+        let rng = &mut test_rng();
+
+        let domain_size = 1 << 9;
+
+        let pcs_params = KZG::<Bls12_381>::setup(domain_size - 1, rng);
+        let ring_builder_key = RingBuilderKey::from_srs(&pcs_params, domain_size);
+        let srs = |range: Range<usize>| Ok(ring_builder_key.lis_in_g1[range].to_vec());
+        let piop_params = PiopParams::rand(domain_size, rng);
+
+        let old_keys = random_vec::<EdwardsAffine, _>(10, rng);
+        let mut new_keys = old_keys.clone();
+        new_keys[2] = EdwardsAffine::rand(rng);
+        new_keys[7] = EdwardsAffine::rand(rng);
+        new_keys.extend(random_vec::<EdwardsAffine, _>(3, rng));
+        let old_ring = TestRing::with_keys(&piop_params, &old_keys, &ring_builder_key);
+        let new_ring = TestRing::with_keys(&piop_params, &new_keys, &ring_builder_key);
+
+        let delta = old_ring.diff(&old_keys, &new_keys, srs).unwrap();
+        assert_eq!(delta.updates.len(), 5);
+        assert_eq!(delta.target, Some(new_ring.id()));
+        let mut serialized = vec![];
+        delta.serialize_compressed(&mut serialized).unwrap();
+        let delta = RingDelta::deserialize_compressed(&serialized[..]).unwrap();
+        let mut ring = old_ring.clone();
+        ring.apply(&delta, srs).unwrap();
+        assert_eq!(ring, new_ring);
+
+        // Removing keys
+        let delta = new_ring.diff(&new_keys, &old_keys[..4], srs).unwrap();
+        let mut ring = new_ring.clone();
+        ring.apply(&delta, srs).unwrap();
+        assert_eq!(
+            ring,
+            TestRing::with_keys(&piop_params, &old_keys[..4], &ring_builder_key)
+        );
+
+        // The delta doesn't apply to another ring.
+        let mut delta = old_ring.diff(&old_keys, &new_keys, srs).unwrap();
+        let mut ring = new_ring.clone();
+        assert!(ring.apply(&delta, srs).is_err());
+        assert_eq!(ring, new_ring);
+        delta.target = None;
+        assert!(ring.apply(&delta, srs).is_ok());
+        assert_ne!(ring, new_ring);
+
+        // The old keys are not the keys of the ring.
+        assert!(old_ring.diff(&new_keys, &old_keys, srs).is_err());
+        // The new keys don't fit the ring.
+        let too_many_keys = random_vec::<EdwardsAffine, _>(old_ring.max_keys + 1, rng);
+        assert!(old_ring.diff(&old_keys, &too_many_keys, srs).is_err());
+        // The SRS is not available.
+        assert!(old_ring.diff(&old_keys, &new_keys, |_| Err(())).is_err());
+
+        // The identity is not a key.
+        let mut keys_with_identity = old_keys.clone();
+        keys_with_identity[3] = EdwardsAffine::zero();
+        assert!(old_ring.diff(&old_keys, &keys_with_identity, srs).is_err());
+        let mut delta = old_ring.diff(&old_keys, &new_keys, srs).unwrap();
+        delta.updates[0].new = EdwardsAffine::zero();
+        let mut ring = old_ring.clone();
+        assert!(ring.apply(&delta, srs).is_err());
+        assert_eq!(ring, old_ring);
+    }
+
+    #[test]
+    fn test_ring_update_proof() {
+        let rng = &mut test_rng();
+
+        let domain_size = 1 << 9;
+
+        let pcs_params = KZG::<Bls12_381>::setup(domain_size - 1, rng);
+        let ring_builder_key = RingBuilderKey::from_srs(&pcs_params, domain_size);
+        let srs = |range: Range<usize>| Ok(ring_builder_key.lis_in_g1[range].to_vec());
+        let piop_params = PiopParams::rand(domain_size, rng);
+        let kzg_vk = pcs_params.raw_vk();
+
+        let keys = random_vec::<EdwardsAffine, _>(12, rng);
+        let (old_keys, new_keys) = keys.split_at(5);
+        let old_ring = TestRing::with_keys(&piop_params, old_keys, &ring_builder_key);
+        let mut new_ring = old_ring.clone();
+        new_ring.append(new_keys, srs);
+
+        let proof = old_ring
+            .prove_append(&new_ring, new_keys, &piop_params, &pcs_params)
+            .unwrap();
+        assert!(old_ring.verify_append(&new_ring, new_keys, &piop_params, &proof, &kzg_vk));
+
+        let other_keys = random_vec::<EdwardsAffine, _>(7, rng);
+        assert!(!old_ring.verify_append(&new_ring, &other_keys, &piop_params, &proof, &kzg_vk));
+        let mut other_ring = old_ring.clone();
+        other_ring.append(&other_keys, srs);
+        assert!(!old_ring.verify_append(&other_ring, new_keys, &piop_params, &proof, &kzg_vk));
+        let proof = old_ring
+            .prove_append(&other_ring, new_keys, &piop_params, &pcs_params)
+            .unwrap();
+        assert!(!old_ring.verify_append(&other_ring, new_keys, &piop_params, &proof, &kzg_vk));
+        assert!(!old_ring.verify_append(&new_ring, &new_keys[1..], &piop_params, &proof, &kzg_vk));
+
+        // The identity is not a key.
+        let mut keys_with_identity = new_keys.to_vec();
+        keys_with_identity[1] = EdwardsAffine::zero();
+        assert!(old_ring
+            .prove_append(&new_ring, &keys_with_identity, &piop_params, &pcs_params)
+            .is_err());
+        assert!(!old_ring.verify_append(
+            &new_ring,
+            &keys_with_identity,
+            &piop_params,
+            &proof,
+            &kzg_vk
+        ));
+    }
+
     fn get_monomial_commitment(
         pcs_params: &URS<Bls12_381>,
         piop_params: &PiopParams<EdwardsAffine>,
@@ -309,7 +961,7 @@ mod tests {
     ) -> (G1Affine, G1Affine) {
         let (_, verifier_key) =
             crate::piop::index::<_, KZG<Bls12_381>, _>(pcs_params, piop_params, keys);
-        let [monimial_cx, monimial_cy] = verifier_key.fixed_columns_committed.points;
+        let [[monimial_cx, monimial_cy]] = verifier_key.fixed_columns_committed.points;
         (monimial_cx.0, monimial_cy.0)
     }
 }
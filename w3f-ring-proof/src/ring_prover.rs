@@ -1,23 +1,50 @@
-use ark_ec::twisted_edwards::{Affine, TECurveConfig};
+use ark_ec::twisted_edwards::Affine;
+use ark_ec::AffineRepr;
 use ark_ff::PrimeField;
+use ark_std::vec::Vec;
 use w3f_pcs::pcs::PCS;
 use w3f_plonk_common::piop::ProverPiop;
 use w3f_plonk_common::prover::PlonkProver;
 use w3f_plonk_common::transcript::PlonkTranscript;
+use w3f_plonk_common::Column;
 
-use crate::piop::params::PiopParams;
-use crate::piop::{FixedColumns, PiopProver, ProverKey};
-use crate::{ArkTranscript, RingProof};
+use crate::keyset::KeyIndex;
+use crate::piop::many::ManyPiopProver;
+use crate::piop::params::{PiopParams, TupleParams};
+use crate::piop::subset::{SubsetPiopProver, SubsetSelector};
+use crate::piop::weighted::{RingWeights, WeightedPiopProver, WeightedProverKey};
+use crate::piop::{
+    CompactFixedColumns, CompactProverKey, FixedColumns, PiopProver, ProverKey, RingCommitments,
+    RingEvaluations,
+};
+use crate::{
+    ArkTranscript, ManyRingProof, RingProof, SubsetRingProof, TupleRingProof, WeightedRingProof,
+};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 
-pub struct RingProver<F, CS, Curve, T = ArkTranscript>
+// Fixed columns either with the evaluations precomputed, or recomputed for every proof.
+enum ProverColumns<F: PrimeField, G: AffineRepr<BaseField = F>> {
+    Expanded(FixedColumns<F, G>),
+    Compact(CompactFixedColumns<F, G>),
+}
+
+/// Prover of membership in the ring of twisted Edwards `Curve` points, see `GenericRingProver`.
+pub type RingProver<F, CS, Curve, T = ArkTranscript> = GenericRingProver<F, CS, Affine<Curve>, T>;
+
+/// Prover of membership in the ring over the commitment scheme `CS`.
+///
+/// The keys are points `G` of an inner curve of any form supported by the PIOP, twisted Edwards or short Weierstrass,
+/// the latter e.g. for the IPA over a curve cycle, see `IpaRingProver`.
+pub struct GenericRingProver<F, CS, G, T = ArkTranscript>
 where
     F: PrimeField,
     CS: PCS<F>,
-    Curve: TECurveConfig<BaseField = F>,
+    G: AffineRepr<BaseField = F>,
     T: PlonkTranscript<F, CS>,
 {
-    piop_params: PiopParams<Affine<Curve>>,
-    fixed_columns: FixedColumns<F, Affine<Curve>>,
+    piop_params: PiopParams<G>,
+    fixed_columns: ProverColumns<F, G>,
     // TODO: We could have a prover that as an optimization stores the commitment to the part of the trace
     // TODO: that depends on the prover's index but not the blinding. That would save some computation,
     // TODO: but the quotient is `O(ring-size)` anyway.
@@ -25,16 +52,23 @@ where
     plonk_prover: PlonkProver<F, CS, T>,
 }
 
-impl<F, CS, Curve, T> RingProver<F, CS, Curve, T>
+impl<F, CS, G, T> GenericRingProver<F, CS, G, T>
 where
     F: PrimeField,
     CS: PCS<F>,
-    Curve: TECurveConfig<BaseField = F>,
+    G: AffineRepr<BaseField = F>,
     T: PlonkTranscript<F, CS>,
+    PiopProver<F, G>: ProverPiop<
+        F,
+        CS::C,
+        Commitments = RingCommitments<F, CS::C>,
+        Evaluations = RingEvaluations<F>,
+        Instance = [G; 1],
+    >,
 {
     pub fn init(
-        prover_key: ProverKey<F, CS, Affine<Curve>>,
-        piop_params: PiopParams<Affine<Curve>>,
+        prover_key: ProverKey<F, CS, G>,
+        piop_params: PiopParams<G>,
         k: usize,
         empty_transcript: T,
     ) -> Self {
@@ -48,31 +82,308 @@ where
 
         Self {
             piop_params,
+            fixed_columns: ProverColumns::Expanded(fixed_columns),
+            k,
+            plonk_prover,
+        }
+    }
+
+    /// Same as `init`, but locates the prover's key `pk` in the ring.
+    /// Fails if the key is not in the ring, or occupies more than one slot.
+    ///
+    /// Scans the whole ring, see `for_key_with_index` to find the key faster.
+    pub fn for_key(
+        prover_key: ProverKey<F, CS, G>,
+        piop_params: PiopParams<G>,
+        pk: G,
+        empty_transcript: T,
+    ) -> Result<Self, ()> {
+        if pk == piop_params.padding {
+            return Err(());
+        }
+        let keyset_part =
+            &prover_key.fixed_columns.points[0].payload()[..piop_params.keyset_part_size];
+        let mut slots = keyset_part
+            .iter()
+            .enumerate()
+            .filter(|(_, key)| **key == pk);
+        let k = match (slots.next(), slots.next()) {
+            (Some((k, _)), None) => k,
+            _ => return Err(()),
+        };
+        Ok(Self::init(prover_key, piop_params, k, empty_transcript))
+    }
+
+    /// Same as `for_key`, but looks the key up in the index prebuilt for the keys of the ring.
+    pub fn for_key_with_index(
+        prover_key: ProverKey<F, CS, G>,
+        piop_params: PiopParams<G>,
+        pk: G,
+        index: &KeyIndex<G>,
+        empty_transcript: T,
+    ) -> Result<Self, ()> {
+        let k = index.position(&pk)?;
+        // The index could have been built for another ring.
+        if k >= piop_params.keyset_part_size
+            || prover_key.fixed_columns.points[0].payload()[k] != pk
+        {
+            return Err(());
+        }
+        Ok(Self::init(prover_key, piop_params, k, empty_transcript))
+    }
+
+    /// Same as `init`, but keeps the fixed columns compact, recomputing their evaluations for every proof.
+    /// Trades some proving time for several times less memory.
+    pub fn init_compact(
+        prover_key: CompactProverKey<F, CS, G>,
+        piop_params: PiopParams<G>,
+        k: usize,
+        empty_transcript: T,
+    ) -> Self {
+        let CompactProverKey {
+            pcs_ck,
             fixed_columns,
+            verifier_key,
+        } = prover_key;
+
+        let plonk_prover = PlonkProver::init(pcs_ck, verifier_key, empty_transcript);
+
+        Self {
+            piop_params,
+            fixed_columns: ProverColumns::Compact(fixed_columns),
             k,
             plonk_prover,
         }
     }
 
-    pub fn prove(&self, t: Curve::ScalarField) -> RingProof<F, CS> {
-        let piop = PiopProver::build(&self.piop_params, self.fixed_columns.clone(), self.k, t);
+    fn fixed_columns(&self) -> FixedColumns<F, G> {
+        match &self.fixed_columns {
+            ProverColumns::Expanded(fixed_columns) => fixed_columns.clone(),
+            ProverColumns::Compact(fixed_columns) => fixed_columns.expand(&self.piop_params.domain),
+        }
+    }
+
+    pub fn prove(&self, t: G::ScalarField) -> RingProof<F, CS> {
+        let piop = PiopProver::build(&self.piop_params, self.fixed_columns(), self.k, t);
         self.plonk_prover.prove(piop)
     }
 
     /// Proof membership of `C_k`, given its index `k`, in the ring `pk.fixed_columns.points` identified by
     /// `vk.fixed_columns_committed.points` and re-randomize the `C_k` to `C' = C_k + rH` with the given `r`.
+    pub fn rerandomize_pk(&self, k: usize, r: G::ScalarField) -> (G, RingProof<F, CS>) {
+        let piop = PiopProver::build(&self.piop_params, self.fixed_columns(), k, r);
+        let blinded_pk = <PiopProver<F, G> as ProverPiop<F, CS::C>>::result(&piop)[0];
+        let proof = self.plonk_prover.prove(piop);
+        (blinded_pk, proof)
+    }
+
+    /// Same as `rerandomize_pk`, but additionally proves that `k` belongs to the subset of the ring slots
+    /// highlighted by `subset_selector`, e.g. to a range of slots, or to the slots of the members with a certain role.
+    /// The verifier should know the commitment to the selector, see `RingVerifier::verify_in_subset`.
+    pub fn rerandomize_pk_in_subset(
+        &self,
+        subset_selector: &SubsetSelector<F, CS::C>,
+        k: usize,
+        r: G::ScalarField,
+    ) -> (G, SubsetRingProof<F, CS>) {
+        let piop = SubsetPiopProver::build(
+            &self.piop_params,
+            self.fixed_columns(),
+            subset_selector.clone(),
+            k,
+            r,
+        );
+        let (blinded_pk, _) =
+            <SubsetPiopProver<F, CS::C, G> as ProverPiop<F, CS::C>>::result(&piop);
+        let proof = self.plonk_prover.prove(piop);
+        (blinded_pk, proof)
+    }
+
+    /// Proves membership of `K` distinct keys `C_k1, ..., C_kK` in the ring with a single proof,
+    /// and re-randomizes every `C_ki` to `C'_ki = C_ki + r_iH`, for every `(k_i, r_i)` in `claims`.
+    /// The blinded keys are returned in the order of the claims.
+    ///
+    /// The proof reveals no more than `K` independent proofs would, but is cheaper to produce and verify.
+    pub fn rerandomize_many<const K: usize>(
+        &self,
+        claims: [(usize, G::ScalarField); K],
+    ) -> (Vec<G>, ManyRingProof<F, CS, K>) {
+        let piop = ManyPiopProver::build(&self.piop_params, self.fixed_columns(), claims);
+        let blinded_pks = <ManyPiopProver<F, G, K> as ProverPiop<F, CS::C>>::result(&piop);
+        let proof = self.plonk_prover.prove(piop);
+        (blinded_pks, proof)
+    }
+
+    /// Batch version of [`rerandomize_pk`](Self::rerandomize_pk): for every `(k, r)` in `claims`
+    /// proves membership of `C_k` and re-randomizes it to `C' = C_k + rH`.
+    ///
+    /// The prover key and the domains are shared between the proofs, but every proof gets its own copy
+    /// of the transcript, so the output is the same as of calling `rerandomize_pk` for each claim in order.
+    /// With the `parallel` feature on, the proofs are generated in parallel.
+    pub fn rerandomize_pk_batch(
+        &self,
+        claims: &[(usize, G::ScalarField)],
+    ) -> Vec<(G, RingProof<F, CS>)>
+    where
+        Self: Sync,
+        RingProof<F, CS>: Send,
+    {
+        ark_std::cfg_iter!(claims)
+            .map(|&(k, r)| self.rerandomize_pk(k, r))
+            .collect()
+    }
+
+    pub fn piop_params(&self) -> &PiopParams<G> {
+        &self.piop_params
+    }
+}
+
+/// Same as `RingProver`, for a ring of `K`-tuples of keys.
+pub type TupleRingProver<F, CS, Curve, const K: usize, T = ArkTranscript> =
+    GenericTupleRingProver<F, CS, Affine<Curve>, K, T>;
+
+/// Same as `GenericRingProver`, for a ring of `K`-tuples of keys.
+pub struct GenericTupleRingProver<F, CS, G, const K: usize, T = ArkTranscript>
+where
+    F: PrimeField,
+    CS: PCS<F>,
+    G: AffineRepr<BaseField = F>,
+    T: PlonkTranscript<F, CS>,
+{
+    params: TupleParams<G, K>,
+    fixed_columns: FixedColumns<F, G, K>,
+    plonk_prover: PlonkProver<F, CS, T>,
+}
+
+impl<F, CS, G, const K: usize, T> GenericTupleRingProver<F, CS, G, K, T>
+where
+    F: PrimeField,
+    CS: PCS<F>,
+    G: AffineRepr<BaseField = F>,
+    T: PlonkTranscript<F, CS>,
+    PiopProver<F, G, K>: ProverPiop<
+        F,
+        CS::C,
+        Commitments = RingCommitments<F, CS::C, K>,
+        Evaluations = RingEvaluations<F, K>,
+        Instance = [G; K],
+    >,
+{
+    pub fn init(
+        prover_key: ProverKey<F, CS, G, K>,
+        params: TupleParams<G, K>,
+        empty_transcript: T,
+    ) -> Self {
+        let ProverKey {
+            pcs_ck,
+            fixed_columns,
+            verifier_key,
+        } = prover_key;
+
+        let plonk_prover = PlonkProver::init(pcs_ck, verifier_key, empty_transcript);
+
+        Self {
+            params,
+            fixed_columns,
+            plonk_prover,
+        }
+    }
+
+    /// Proves membership of the key tuple `(C_k1, ..., C_kK)`, given its index `k`, in the ring,
+    /// and re-randomizes the keys to `C'_kj = C_kj + rH_j` with the given `r`.
+    /// The blinded keys are returned in the order of the tuple components.
+    pub fn rerandomize_pks(
+        &self,
+        k: usize,
+        r: G::ScalarField,
+    ) -> (Vec<G>, TupleRingProof<F, CS, K>) {
+        let piop = PiopProver::build(&self.params.piop_params, self.fixed_columns.clone(), k, r);
+        let blinded_pks = <PiopProver<F, G, K> as ProverPiop<F, CS::C>>::result(&piop);
+        let proof = self.plonk_prover.prove(piop);
+        (blinded_pks.to_vec(), proof)
+    }
+
+    pub fn params(&self) -> &TupleParams<G, K> {
+        &self.params
+    }
+}
+
+/// Prover of a lower bound on the weight of a ring member, for a weighted ring, see `index_weighted`.
+pub type WeightedRingProver<F, CS, Curve, T = ArkTranscript> =
+    GenericWeightedRingProver<F, CS, Affine<Curve>, T>;
+
+/// Same as `GenericRingProver`, for a weighted ring.
+pub struct GenericWeightedRingProver<F, CS, G, T = ArkTranscript>
+where
+    F: PrimeField,
+    CS: PCS<F>,
+    G: AffineRepr<BaseField = F>,
+    T: PlonkTranscript<F, CS>,
+{
+    piop_params: PiopParams<G>,
+    fixed_columns: FixedColumns<F, G>,
+    weights: RingWeights<F>,
+    plonk_prover: PlonkProver<F, CS, T>,
+}
+
+impl<F, CS, G, T> GenericWeightedRingProver<F, CS, G, T>
+where
+    F: PrimeField,
+    CS: PCS<F>,
+    G: AffineRepr<BaseField = F>,
+    T: PlonkTranscript<F, CS>,
+    PiopProver<F, G>: ProverPiop<
+        F,
+        CS::C,
+        Commitments = RingCommitments<F, CS::C>,
+        Evaluations = RingEvaluations<F>,
+        Instance = [G; 1],
+    >,
+{
+    pub fn init(
+        prover_key: WeightedProverKey<F, CS, G>,
+        piop_params: PiopParams<G>,
+        empty_transcript: T,
+    ) -> Self {
+        let verifier_key = prover_key.verifier_key();
+        let WeightedProverKey {
+            prover_key,
+            weights,
+            ..
+        } = prover_key;
+
+        let plonk_prover = PlonkProver::init(prover_key.pcs_ck, verifier_key, empty_transcript);
+
+        Self {
+            piop_params,
+            fixed_columns: prover_key.fixed_columns,
+            weights,
+            plonk_prover,
+        }
+    }
+
+    /// Same as `RingProver::rerandomize_pk`, but additionally proves that the weight of `k` (e.g. its stake)
+    /// is at least `threshold`, without revealing `k` or the weight.
     pub fn rerandomize_pk(
         &self,
+        threshold: u64,
         k: usize,
-        r: Curve::ScalarField,
-    ) -> (Affine<Curve>, RingProof<F, CS>) {
-        let piop = PiopProver::build(&self.piop_params, self.fixed_columns.clone(), k, r);
-        let blinded_pk = <PiopProver<F, Affine<Curve>> as ProverPiop<F, CS::C>>::result(&piop);
+        r: G::ScalarField,
+    ) -> (G, WeightedRingProof<F, CS>) {
+        let piop = WeightedPiopProver::build(
+            &self.piop_params,
+            self.fixed_columns.clone(),
+            self.weights.clone(),
+            threshold,
+            k,
+            r,
+        );
+        let (blinded_pk, _) = <WeightedPiopProver<F, G> as ProverPiop<F, CS::C>>::result(&piop);
         let proof = self.plonk_prover.prove(piop);
         (blinded_pk, proof)
     }
 
-    pub fn piop_params(&self) -> &PiopParams<Affine<Curve>> {
+    pub fn piop_params(&self) -> &PiopParams<G> {
         &self.piop_params
     }
 }
@@ -1,40 +1,54 @@
 use ark_ec::pairing::Pairing;
 use ark_ec::twisted_edwards::{Affine, TECurveConfig};
-use ark_ec::CurveGroup;
+use ark_ec::{AffineRepr, CurveGroup};
 use ark_ff::PrimeField;
 use w3f_pcs::pcs::kzg::KZG;
 use w3f_pcs::pcs::{RawVerifierKey, PCS};
+use w3f_plonk_common::batch_verifier::{PlonkBatchVerifier, PreparedItem};
+use w3f_plonk_common::piop::VerifierPiop;
 use w3f_plonk_common::transcript::PlonkTranscript;
-use w3f_plonk_common::verifier::PlonkVerifier;
+use w3f_plonk_common::verifier::{Challenges, PlonkVerifier};
 
-use crate::multi_ring_batch_verifier::BatchVerifier;
-use crate::piop::params::PiopParams;
-use crate::piop::{FixedColumnsCommitted, PiopVerifier, VerifierKey};
-use crate::{ArkTranscript, RingProof};
+use crate::multi_ring_batch_verifier::{BatchItem, BatchVerifier};
+use crate::piop::many::ManyPiopVerifier;
+use crate::piop::params::{PiopParams, TupleParams};
+use crate::piop::subset::{SubsetPiopVerifier, SubsetRingEvaluations};
+use crate::piop::weighted::{WeightedPiopVerifier, WeightedVerifierKey, WeightsCommitted};
+use crate::piop::{FixedColumnsCommitted, PiopVerifier, RingCommitments, VerifierKey};
+use crate::{
+    ArkTranscript, ManyRingProof, RingProof, SubsetRingProof, TupleRingProof, WeightedRingProof,
+};
+use ark_std::rand::RngCore;
 use ark_std::vec::Vec;
 
-pub struct RingVerifier<F, CS, Jubjub, T = ArkTranscript>
+/// Verifier of membership in the ring of twisted Edwards `Curve` points, see `GenericRingVerifier`.
+pub type RingVerifier<F, CS, Curve, T = ArkTranscript> =
+    GenericRingVerifier<F, CS, Affine<Curve>, T>;
+
+/// Verifier of membership in the ring, see `GenericRingProver`.
+pub struct GenericRingVerifier<F, CS, G, T = ArkTranscript>
 where
     F: PrimeField,
     CS: PCS<F>,
-    Jubjub: TECurveConfig<BaseField = F>,
+    G: AffineRepr<BaseField = F>,
     T: PlonkTranscript<F, CS>,
 {
-    pub(crate) piop_params: PiopParams<Affine<Jubjub>>,
+    pub(crate) piop_params: PiopParams<G>,
     pub(crate) fixed_columns_committed: FixedColumnsCommitted<F, CS::C>,
     pub(crate) plonk_verifier: PlonkVerifier<F, CS, T>,
 }
 
-impl<F, CS, Jubjub, T> RingVerifier<F, CS, Jubjub, T>
+impl<F, CS, G, T> GenericRingVerifier<F, CS, G, T>
 where
     F: PrimeField,
     CS: PCS<F>,
-    Jubjub: TECurveConfig<BaseField = F>,
+    G: AffineRepr<BaseField = F>,
     T: PlonkTranscript<F, CS>,
+    PiopVerifier<F, CS::C, G>: VerifierPiop<F, CS::C, Instance = [G; 1]>,
 {
     pub fn init(
         verifier_key: VerifierKey<F, CS>,
-        piop_params: PiopParams<Affine<Jubjub>>,
+        piop_params: PiopParams<G>,
         empty_transcript: T,
     ) -> Self {
         let pcs_vk = verifier_key.pcs_raw_vk.prepare();
@@ -46,27 +60,97 @@ where
         }
     }
 
-    pub fn verify(&self, proof: RingProof<F, CS>, result: Affine<Jubjub>) -> bool {
+    pub fn verify(&self, proof: RingProof<F, CS>, result: G) -> bool {
         let (challenges, mut fs_rng) = self
             .plonk_verifier
-            .restore_fs_with_rng::<PiopVerifier<_, _, Affine<Jubjub>>, _, _>(&result, &proof);
+            .restore_fs_with_rng::<PiopVerifier<_, _, G>, _, _>(&[result], &proof);
+        let seed = self.piop_params.seed;
+        let seed_plus_result = (seed + result).into_affine();
+        let domain_at_zeta = self.piop_params.domain.evaluate(challenges.zeta);
+        let piop = PiopVerifier::<_, _, G>::init(
+            domain_at_zeta,
+            self.fixed_columns_committed.clone(),
+            proof.column_commitments.clone(),
+            proof.columns_at_zeta.clone(),
+            seed.xy().unwrap(),
+            [seed_plus_result.xy().unwrap()],
+        );
+
+        self.plonk_verifier
+            .verify(piop, proof, challenges, &mut fs_rng)
+    }
+
+    /// Verifies a proof of membership in the subset of the ring, given the commitment to its selector.
+    /// See `RingProver::rerandomize_pk_in_subset`.
+    pub fn verify_in_subset(
+        &self,
+        proof: SubsetRingProof<F, CS>,
+        result: G,
+        subset_selector: CS::C,
+    ) -> bool {
+        let (piop, challenges, mut fs_rng) = self.subset_piop(&proof, result, subset_selector);
+        self.plonk_verifier
+            .verify(piop, proof, challenges, &mut fs_rng)
+    }
+
+    fn subset_piop(
+        &self,
+        proof: &SubsetRingProof<F, CS>,
+        result: G,
+        subset_selector: CS::C,
+    ) -> (SubsetPiopVerifier<F, CS::C, G>, Challenges<F>, impl RngCore) {
+        let instance = (result, subset_selector.clone());
+        let (challenges, fs_rng) = self
+            .plonk_verifier
+            .restore_fs_with_rng::<SubsetPiopVerifier<_, _, G>, _, _>(&instance, proof);
         let seed = self.piop_params.seed;
         let seed_plus_result = (seed + result).into_affine();
         let domain_at_zeta = self.piop_params.domain.evaluate(challenges.zeta);
-        let piop = PiopVerifier::<_, _, Affine<Jubjub>>::init(
+        let piop = SubsetPiopVerifier::<_, _, G>::init(
             domain_at_zeta,
             self.fixed_columns_committed.clone(),
+            subset_selector,
             proof.column_commitments.clone(),
             proof.columns_at_zeta.clone(),
-            (seed.x, seed.y),
-            (seed_plus_result.x, seed_plus_result.y),
+            seed.xy().unwrap(),
+            seed_plus_result.xy().unwrap(),
+        );
+        (piop, challenges, fs_rng)
+    }
+
+    /// Verifies a proof of membership of `K` distinct ring members, see `RingProver::rerandomize_many`.
+    /// The blinded keys should be given in the order of the prover's claims.
+    pub fn verify_many<const K: usize>(
+        &self,
+        proof: ManyRingProof<F, CS, K>,
+        results: Vec<G>,
+    ) -> bool {
+        if results.len() != K {
+            return false;
+        }
+        let (challenges, mut fs_rng) = self
+            .plonk_verifier
+            .restore_fs_with_rng::<ManyPiopVerifier<_, _, G, K>, _, _>(&results, &proof);
+        let seed = self.piop_params.seed;
+        let seed_plus_results = ark_std::array::from_fn(|i| {
+            let seed_plus_result = (seed + results[i]).into_affine();
+            seed_plus_result.xy().unwrap()
+        });
+        let domain_at_zeta = self.piop_params.domain.evaluate(challenges.zeta);
+        let piop = ManyPiopVerifier::<_, _, G, K>::init(
+            domain_at_zeta,
+            self.fixed_columns_committed.clone(),
+            proof.column_commitments.clone(),
+            proof.columns_at_zeta.clone(),
+            seed.xy().unwrap(),
+            seed_plus_results,
         );
 
         self.plonk_verifier
             .verify(piop, proof, challenges, &mut fs_rng)
     }
 
-    pub fn piop_params(&self) -> &PiopParams<Affine<Jubjub>> {
+    pub fn piop_params(&self) -> &PiopParams<G> {
         &self.piop_params
     }
 
@@ -76,11 +160,7 @@ where
 
     /// Verifies the proofs sequentially, pairing them with the results
     /// positionally. Fails if the two vectors differ in length.
-    pub fn verify_batch(
-        &self,
-        proofs: Vec<RingProof<F, CS>>,
-        results: Vec<Affine<Jubjub>>,
-    ) -> bool {
+    pub fn verify_batch(&self, proofs: Vec<RingProof<F, CS>>, results: Vec<G>) -> bool {
         if proofs.len() != results.len() {
             return false;
         }
@@ -121,4 +201,214 @@ where
         }
         batch.verify()
     }
+
+    /// Prepares a proof of membership in the subset of the ring for batch verification
+    /// together with the proofs of other kinds, see `PlonkBatchVerifier` and `BatchItem::into_prepared`.
+    pub fn prepare_in_subset(
+        &self,
+        proof: SubsetRingProof<E::ScalarField, KZG<E>>,
+        result: Affine<J>,
+        subset_selector: <KZG<E> as PCS<E::ScalarField>>::C,
+    ) -> PreparedItem<
+        E,
+        SubsetPiopVerifier<E::ScalarField, <KZG<E> as PCS<E::ScalarField>>::C, Affine<J>>,
+        RingCommitments<E::ScalarField, <KZG<E> as PCS<E::ScalarField>>::C>,
+        SubsetRingEvaluations<E::ScalarField>,
+    > {
+        let (piop, challenges, mut fs_rng) = self.subset_piop(&proof, result, subset_selector);
+        PreparedItem::new(piop, proof, challenges, &mut fs_rng)
+    }
+
+    /// Same as `verify_batch_kzg`, but with the `parallel` feature on prepares and accumulates the proofs in parallel,
+    /// see `BatchVerifier::push_many`.
+    pub fn verify_batch_kzg_par(
+        &self,
+        proofs: Vec<RingProof<E::ScalarField, KZG<E>>>,
+        results: Vec<Affine<J>>,
+    ) -> bool
+    where
+        Self: Sync,
+        RingProof<E::ScalarField, KZG<E>>: Send,
+        BatchItem<E, J>: Send,
+        PlonkBatchVerifier<E, T>: Send + Sync,
+    {
+        if proofs.len() != results.len() {
+            return false;
+        }
+        let mut batch = BatchVerifier::new(
+            self.plonk_verifier.pcs_vk.clone(),
+            self.plonk_verifier.transcript_prelude.clone(),
+        );
+        let items = proofs
+            .into_iter()
+            .zip(results)
+            .map(|(proof, result)| (self, proof, result))
+            .collect();
+        batch.push_many(items);
+        batch.verify()
+    }
+
+    /// Same as `verify_batch_kzg`, but on failure locates the failing proofs,
+    /// see `BatchVerifier::verify_and_identify`.
+    /// Returns the indices of the failing proofs, empty if all the proofs are valid.
+    /// Fails if the two vectors differ in length.
+    pub fn verify_batch_kzg_and_identify(
+        &self,
+        proofs: Vec<RingProof<E::ScalarField, KZG<E>>>,
+        results: Vec<Affine<J>>,
+    ) -> Result<Vec<usize>, ()> {
+        if proofs.len() != results.len() {
+            return Err(());
+        }
+        let mut batch = BatchVerifier::new(
+            self.plonk_verifier.pcs_vk.clone(),
+            self.plonk_verifier.transcript_prelude.clone(),
+        );
+        for (proof, result) in proofs.into_iter().zip(results) {
+            batch.push(self, proof, result);
+        }
+        Ok(batch.verify_and_identify())
+    }
+}
+
+/// Verifier of a lower bound on the weight of a ring member, see `WeightedRingProver`.
+pub type WeightedRingVerifier<F, CS, Curve, T = ArkTranscript> =
+    GenericWeightedRingVerifier<F, CS, Affine<Curve>, T>;
+
+/// Same as `GenericRingVerifier`, for a weighted ring.
+pub struct GenericWeightedRingVerifier<F, CS, G, T = ArkTranscript>
+where
+    F: PrimeField,
+    CS: PCS<F>,
+    G: AffineRepr<BaseField = F>,
+    T: PlonkTranscript<F, CS>,
+{
+    piop_params: PiopParams<G>,
+    fixed_columns_committed: FixedColumnsCommitted<F, CS::C>,
+    weights: WeightsCommitted<F, CS::C>,
+    plonk_verifier: PlonkVerifier<F, CS, T>,
+}
+
+impl<F, CS, G, T> GenericWeightedRingVerifier<F, CS, G, T>
+where
+    F: PrimeField,
+    CS: PCS<F>,
+    G: AffineRepr<BaseField = F>,
+    T: PlonkTranscript<F, CS>,
+    PiopVerifier<F, CS::C, G>: VerifierPiop<F, CS::C, Instance = [G; 1]>,
+{
+    pub fn init(
+        verifier_key: WeightedVerifierKey<F, CS>,
+        piop_params: PiopParams<G>,
+        empty_transcript: T,
+    ) -> Self {
+        let pcs_vk = verifier_key.verifier_key.pcs_raw_vk.prepare();
+        let plonk_verifier = PlonkVerifier::init(pcs_vk, &verifier_key, empty_transcript);
+        Self {
+            piop_params,
+            fixed_columns_committed: verifier_key.verifier_key.fixed_columns_committed,
+            weights: verifier_key.weights,
+            plonk_verifier,
+        }
+    }
+
+    /// Verifies a proof that the weight of the ring member is at least `threshold`,
+    /// see `WeightedRingProver::rerandomize_pk`.
+    pub fn verify(&self, proof: WeightedRingProof<F, CS>, result: G, threshold: u64) -> bool {
+        let instance = (result, threshold);
+        let (challenges, mut fs_rng) = self
+            .plonk_verifier
+            .restore_fs_with_rng::<WeightedPiopVerifier<_, _, G>, _, _>(&instance, &proof);
+        let seed = self.piop_params.seed;
+        let seed_plus_result = (seed + result).into_affine();
+        let domain_at_zeta = self.piop_params.domain.evaluate(challenges.zeta);
+        let piop = WeightedPiopVerifier::<_, _, G>::init(
+            domain_at_zeta,
+            self.fixed_columns_committed.clone(),
+            self.weights.clone(),
+            threshold,
+            proof.column_commitments.clone(),
+            proof.columns_at_zeta.clone(),
+            seed.xy().unwrap(),
+            seed_plus_result.xy().unwrap(),
+        );
+
+        self.plonk_verifier
+            .verify(piop, proof, challenges, &mut fs_rng)
+    }
+
+    pub fn piop_params(&self) -> &PiopParams<G> {
+        &self.piop_params
+    }
+}
+
+/// Same as `RingVerifier`, for a ring of `K`-tuples of keys.
+pub type TupleRingVerifier<F, CS, Curve, const K: usize, T = ArkTranscript> =
+    GenericTupleRingVerifier<F, CS, Affine<Curve>, K, T>;
+
+/// Same as `GenericRingVerifier`, for a ring of `K`-tuples of keys.
+pub struct GenericTupleRingVerifier<F, CS, G, const K: usize, T = ArkTranscript>
+where
+    F: PrimeField,
+    CS: PCS<F>,
+    G: AffineRepr<BaseField = F>,
+    T: PlonkTranscript<F, CS>,
+{
+    params: TupleParams<G, K>,
+    fixed_columns_committed: FixedColumnsCommitted<F, CS::C, K>,
+    plonk_verifier: PlonkVerifier<F, CS, T>,
+}
+
+impl<F, CS, G, const K: usize, T> GenericTupleRingVerifier<F, CS, G, K, T>
+where
+    F: PrimeField,
+    CS: PCS<F>,
+    G: AffineRepr<BaseField = F>,
+    T: PlonkTranscript<F, CS>,
+    PiopVerifier<F, CS::C, G, K>: VerifierPiop<F, CS::C, Instance = [G; K]>,
+{
+    pub fn init(
+        verifier_key: VerifierKey<F, CS, K>,
+        params: TupleParams<G, K>,
+        empty_transcript: T,
+    ) -> Self {
+        let pcs_vk = verifier_key.pcs_raw_vk.prepare();
+        let plonk_verifier = PlonkVerifier::init(pcs_vk, &verifier_key, empty_transcript);
+        Self {
+            params,
+            fixed_columns_committed: verifier_key.fixed_columns_committed,
+            plonk_verifier,
+        }
+    }
+
+    /// Verifies the proof against the blinded keys, given in the order of the tuple components.
+    pub fn verify(&self, proof: TupleRingProof<F, CS, K>, results: Vec<G>) -> bool {
+        let Ok(results) = <[G; K]>::try_from(results) else {
+            return false;
+        };
+        let (challenges, mut fs_rng) = self
+            .plonk_verifier
+            .restore_fs_with_rng::<PiopVerifier<_, _, G, K>, _, _>(&results, &proof);
+        let seed = self.params.piop_params.seed;
+        let seed_plus_results = ark_std::array::from_fn(|j| {
+            let seed_plus_result = (seed + results[j]).into_affine();
+            seed_plus_result.xy().unwrap()
+        });
+        let domain_at_zeta = self.params.piop_params.domain.evaluate(challenges.zeta);
+        let piop = PiopVerifier::<_, _, G, K>::init(
+            domain_at_zeta,
+            self.fixed_columns_committed.clone(),
+            proof.column_commitments.clone(),
+            proof.columns_at_zeta.clone(),
+            seed.xy().unwrap(),
+            seed_plus_results,
+        );
+
+        self.plonk_verifier
+            .verify(piop, proof, challenges, &mut fs_rng)
+    }
+
+    pub fn params(&self) -> &TupleParams<G, K> {
+        &self.params
+    }
 }
@@ -0,0 +1,141 @@
+//! Traceable ring proofs: the prover stays anonymous to everyone but a designated tracer, who can recover the prover's key.
+//!
+//! A traceable proof is a proof for the ring of the key pairs `(pk, pk)`, blinded with the bases `(H, T)`,
+//! where `T = tH` is the tracer's public key (see `TupleParams`). The blinded keys `C1 = pk + rH` and `C2 = pk + rT`
+//! form an ElGamal-style encryption of `pk` to the tracer, the correctness of which is guaranteed by the ring proof
+//! itself: the same `CondAdd` gadget is driven by the same bits column for both components.
+//! Knowing `t`, the tracer recovers `pk = (tC1 - C2) / (t - 1)`, and proves the opening correct with
+//! a Chaum-Pedersen proof of `log_H(T) = log_{C1 - pk}(C2 - pk)`.
+
+use ark_ec::twisted_edwards::{Affine, TECurveConfig};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{Field, One, PrimeField, Zero};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::Rng;
+use ark_std::vec::Vec;
+use ark_std::UniformRand;
+
+use crate::piop::params::{PiopParams, TupleParams};
+
+/// Parameters of the traceable ring: the blinded keys are `pk + rH` and `pk + rT`, where `T` is the tracer's key.
+pub fn traceable_params<Curve: TECurveConfig>(
+    piop_params: PiopParams<Affine<Curve>>,
+    tracer_pk: Affine<Curve>,
+) -> TupleParams<Affine<Curve>, 2> {
+    let h = piop_params.h;
+    TupleParams::setup(piop_params, [h, tracer_pk])
+}
+
+/// Keys of the traceable ring: every key is paired with itself, see `traceable_params`.
+pub fn traceable_keys<G: AffineRepr>(keys: &[G]) -> Vec<[G; 2]> {
+    keys.iter().map(|&pk| [pk, pk]).collect()
+}
+
+/// Proof that the traced key is the key encrypted to the tracer, see `Tracer::trace`.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct OpeningProof<F: PrimeField> {
+    challenge: F,
+    response: F,
+}
+
+/// The party that can recover the keys from the traceable proofs.
+pub struct Tracer<Curve: TECurveConfig> {
+    h: Affine<Curve>,
+    sk: Curve::ScalarField,
+    pk: Affine<Curve>,
+}
+
+impl<Curve: TECurveConfig> Tracer<Curve> {
+    /// - `h`: The blinding base of the ring, `PiopParams::h`.
+    /// - `sk`: The tracer's secret key, should be random.
+    pub fn new(h: Affine<Curve>, sk: Curve::ScalarField) -> Self {
+        // With `t = 1` the blinded keys coincide, and with `t = 0` the second one is the key itself.
+        assert!(!sk.is_zero() && !sk.is_one());
+        let pk = (h * sk).into_affine();
+        Self { h, sk, pk }
+    }
+
+    pub fn rand<R: Rng>(h: Affine<Curve>, rng: &mut R) -> Self {
+        Self::new(h, Curve::ScalarField::rand(rng))
+    }
+
+    pub fn pk(&self) -> Affine<Curve> {
+        self.pk
+    }
+
+    /// Recovers the key from the blinded keys `[pk + rH, pk + rT]` output by a traceable proof,
+    /// and proves the recovered key correct, see `verify_opening`.
+    ///
+    /// The ring proof should be verified beforehand, otherwise the result is meaningless.
+    pub fn trace(
+        &self,
+        blinded_pks: &[Affine<Curve>],
+    ) -> Result<(Affine<Curve>, OpeningProof<Curve::ScalarField>), ()> {
+        let [c1, c2] = blinded_pks else {
+            return Err(());
+        };
+        let t = self.sk;
+        let pk =
+            ((*c1 * t - c2) * (t - Curve::ScalarField::one()).inverse().unwrap()).into_affine();
+
+        let d1 = (c1.into_group() - pk).into_affine();
+        let mut transcript = opening_transcript(&self.h, &self.pk, c1, c2, &pk);
+        let mut nonce_transcript = transcript.clone();
+        nonce_transcript.label(b"nonce");
+        nonce_transcript.append(&self.sk);
+        let w: Curve::ScalarField = nonce_transcript.challenge(b"nonce").read_reduce();
+        let a1 = (self.h * w).into_affine();
+        let a2 = (d1 * w).into_affine();
+        let challenge = opening_challenge(&mut transcript, &a1, &a2);
+        let response = w + challenge * t;
+        Ok((
+            pk,
+            OpeningProof {
+                challenge,
+                response,
+            },
+        ))
+    }
+}
+
+/// Verifies that `pk` is the key encrypted to the tracer with the key `tracer_pk` in the `blinded_pks`.
+pub fn verify_opening<Curve: TECurveConfig>(
+    h: Affine<Curve>,
+    tracer_pk: Affine<Curve>,
+    blinded_pks: &[Affine<Curve>],
+    pk: Affine<Curve>,
+    proof: &OpeningProof<Curve::ScalarField>,
+) -> bool {
+    let [c1, c2] = blinded_pks else {
+        return false;
+    };
+    let d1 = c1.into_group() - pk;
+    let d2 = c2.into_group() - pk;
+    let a1 = (h * proof.response - tracer_pk * proof.challenge).into_affine();
+    let a2 = (d1 * proof.response - d2 * proof.challenge).into_affine();
+    let mut transcript = opening_transcript(&h, &tracer_pk, c1, c2, &pk);
+    proof.challenge == opening_challenge(&mut transcript, &a1, &a2)
+}
+
+fn opening_transcript<Curve: TECurveConfig>(
+    h: &Affine<Curve>,
+    tracer_pk: &Affine<Curve>,
+    c1: &Affine<Curve>,
+    c2: &Affine<Curve>,
+    pk: &Affine<Curve>,
+) -> ark_transcript::Transcript {
+    let mut transcript = ark_transcript::Transcript::new_labeled(b"w3f-ring-proof-tracing");
+    transcript.label(b"statement");
+    transcript.append(&[*h, *tracer_pk, *c1, *c2, *pk]);
+    transcript
+}
+
+fn opening_challenge<Curve: TECurveConfig>(
+    transcript: &mut ark_transcript::Transcript,
+    a1: &Affine<Curve>,
+    a2: &Affine<Curve>,
+) -> Curve::ScalarField {
+    transcript.label(b"commitments");
+    transcript.append(&[*a1, *a2]);
+    transcript.challenge(b"challenge").read_reduce()
+}
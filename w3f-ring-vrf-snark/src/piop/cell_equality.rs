@@ -27,7 +27,7 @@ impl<F: FftField> CellEqualityPolys<F> {
         let a_last = a.evals.evals[domain.capacity - 1];
         let b_last = b.evals.evals[domain.capacity - 1];
         assert_eq!(a_last, b_last);
-        let l_last = domain.l_last.clone();
+        let l_last = domain.l_last();
         Self { a, b, l_last }
     }
 
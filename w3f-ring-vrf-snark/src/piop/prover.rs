@@ -281,32 +281,32 @@ where
             self.pks_equal_y.constraints(),
             vec![FixedCells::constraint_cell(
                 &self.pk_from_sk.acc.xs,
-                &self.domain.l_first,
+                &self.domain.l_first(),
                 0,
             )],
             vec![FixedCells::constraint_cell(
                 &self.pk_from_sk.acc.ys,
-                &self.domain.l_first,
+                &self.domain.l_first(),
                 0,
             )],
             vec![FixedCells::constraint_cell(
                 &self.pk_from_index.acc.xs,
-                &self.domain.l_first,
+                &self.domain.l_first(),
                 0,
             )],
             vec![FixedCells::constraint_cell(
                 &self.pk_from_index.acc.ys,
-                &self.domain.l_first,
+                &self.domain.l_first(),
                 0,
             )],
             vec![FixedCells::constraint_cell(
                 &self.doublings_of_in_gadget.doublings.xs,
-                &self.domain.l_first,
+                &self.domain.l_first(),
                 0,
             )],
             vec![FixedCells::constraint_cell(
                 &self.doublings_of_in_gadget.doublings.ys,
-                &self.domain.l_first,
+                &self.domain.l_first(),
                 0,
             )],
         ]